@@ -0,0 +1,71 @@
+use std::alloc::System;
+
+use benches::batch;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use stats_alloc::{Region, StatsAlloc, INSTRUMENTED_SYSTEM};
+
+#[global_allocator]
+static GLOBAL: &StatsAlloc<System> = &INSTRUMENTED_SYSTEM;
+
+/// Number of orders and of items in each order.
+const SIZES: &[(&str, usize, usize)] = &[("small", 1, 4), ("large", 200, 32)];
+
+/// Prints the allocations made by a single run of `f`, which criterion does not measure.
+fn report_allocations<T>(name: &str, f: impl FnOnce() -> T) {
+    let region = Region::new(GLOBAL);
+    let value = f();
+    let stats = region.change();
+    drop(value);
+    println!(
+        "{}: {} allocations, {} reallocations, {} bytes",
+        name, stats.allocations, stats.reallocations, stats.bytes_allocated
+    );
+}
+
+macro_rules! bench_generator {
+    ($c:expr, $generator:literal, $module:ident) => {
+        for &(size, orders, items) in SIZES {
+            let message = batch!(benches::$module, orders, items);
+            let json = serde_json::to_string(&message).unwrap();
+            let id = BenchmarkId::new($generator, size);
+
+            report_allocations(&format!("serialize/{}/{}", $generator, size), || {
+                serde_json::to_string(&message).unwrap()
+            });
+            report_allocations(&format!("deserialize/{}/{}", $generator, size), || {
+                serde_json::from_str::<benches::$module::Batch>(&json).unwrap()
+            });
+
+            let mut group = $c.benchmark_group("serialize");
+            group.throughput(Throughput::Bytes(json.len() as u64));
+            group.bench_with_input(id.clone(), &message, |b, message| {
+                b.iter(|| serde_json::to_string(black_box(message)).unwrap())
+            });
+            group.finish();
+
+            let mut group = $c.benchmark_group("deserialize");
+            group.throughput(Throughput::Bytes(json.len() as u64));
+            group.bench_with_input(id, &json, |b, json| {
+                b.iter(|| serde_json::from_str::<benches::$module::Batch>(black_box(json)).unwrap())
+            });
+            group.finish();
+        }
+    };
+}
+
+fn prost_serde_derive(c: &mut Criterion) {
+    bench_generator!(c, "prost-serde-derive", derive);
+}
+
+fn pbjson(c: &mut Criterion) {
+    bench_generator!(c, "pbjson", pbjson);
+}
+
+// not protobuf JSON, e.g. enums are numbers and bytes are arrays, but a baseline of what serde
+// can do with the same structs
+fn serde_derive(c: &mut Criterion) {
+    bench_generator!(c, "serde_derive", serde);
+}
+
+criterion_group!(benches, prost_serde_derive, pbjson, serde_derive);
+criterion_main!(benches);
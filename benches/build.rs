@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+use std::{env, fs};
+
+const PROTOS: &[&str] = &["proto/bench.proto"];
+
+fn out_dir(name: &str) -> PathBuf {
+    let path = PathBuf::from(env::var("OUT_DIR").unwrap()).join(name);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+fn main() {
+    // the same messages are generated three times, once for each of the compared generators
+    prost_build::Config::new()
+        .out_dir(out_dir("derive"))
+        .type_attribute(
+            ".bench",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .compile_protos(PROTOS, &["proto"])
+        .unwrap();
+
+    prost_build::Config::new()
+        .out_dir(out_dir("serde"))
+        .type_attribute(".bench", "#[derive(serde::Deserialize, serde::Serialize)]")
+        .compile_protos(PROTOS, &["proto"])
+        .unwrap();
+
+    let pbjson_dir = out_dir("pbjson");
+    let descriptor_path = pbjson_dir.join("descriptor.bin");
+    prost_build::Config::new()
+        .out_dir(&pbjson_dir)
+        .file_descriptor_set_path(&descriptor_path)
+        .compile_protos(PROTOS, &["proto"])
+        .unwrap();
+    pbjson_build::Builder::new()
+        .out_dir(&pbjson_dir)
+        .register_descriptors(&fs::read(descriptor_path).unwrap())
+        .unwrap()
+        .build(&[".bench"])
+        .unwrap();
+}
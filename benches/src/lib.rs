@@ -0,0 +1,64 @@
+//! Messages for comparing the code generated by `prost-serde-derive`, pbjson-build and plain
+//! `serde_derive` for the same protobuf definitions.
+
+pub mod derive {
+    include!(concat!(env!("OUT_DIR"), "/derive/bench.rs"));
+}
+
+// generated code, which is not ours to lint
+#[allow(clippy::all)]
+pub mod pbjson {
+    include!(concat!(env!("OUT_DIR"), "/pbjson/bench.rs"));
+    include!(concat!(env!("OUT_DIR"), "/pbjson/bench.serde.rs"));
+}
+
+pub mod serde {
+    include!(concat!(env!("OUT_DIR"), "/serde/bench.rs"));
+}
+
+/// Builds a batch of `orders` orders with `items` items each out of the messages of `$module`.
+#[macro_export]
+macro_rules! batch {
+    ($module:path, $orders:expr, $items:expr) => {{
+        use $module as m;
+
+        let orders = (0..$orders)
+            .map(|i: usize| m::Order {
+                id: format!("order-{:08}", i),
+                created_at: 1_700_000_000_000 + i as i64,
+                customer: Some(m::Customer {
+                    id: i as u64 * 7919,
+                    name: format!("Customer {}", i),
+                    email: format!("customer{}@example.com", i),
+                    phone: (i % 2 == 0).then(|| format!("+1-555-{:04}", i % 10_000)),
+                }),
+                items: (0..$items)
+                    .map(|j: usize| m::Item {
+                        sku: format!("SKU-{}-{}", i, j),
+                        quantity: (j % 5) as u32 + 1,
+                        price: 9.99 + j as f64,
+                        thumbnail: (0..64).map(|b: usize| (b * j) as u8).collect(),
+                    })
+                    .collect(),
+                status: m::Status::Paid as i32,
+                history: vec![m::Status::Pending as i32, m::Status::Paid as i32],
+                signature: (0..32).map(|b: usize| (b + i) as u8).collect(),
+                payment: Some(match i % 3 {
+                    0 => m::order::Payment::Card(m::Card {
+                        number: "4111111111111111".to_string(),
+                        expiry_month: 12,
+                        expiry_year: 2030,
+                    }),
+                    1 => m::order::Payment::Voucher(format!("VOUCHER-{}", i)),
+                    _ => m::order::Payment::Token(vec![0xab; 16]),
+                }),
+                tags: vec!["priority".to_string(), format!("region-{}", i % 4)],
+            })
+            .collect();
+
+        m::Batch {
+            orders,
+            sequence: (0..$orders as i64).collect(),
+        }
+    }};
+}
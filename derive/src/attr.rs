@@ -1,9 +1,18 @@
+use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_quote, Attribute, Expr, ExprLit, Lit, Meta, Path};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Attribute, Expr, ExprLit, Ident, Lifetime, Lit, Meta, MetaNameValue, Path, Type,
+};
 
 use crate::context::Context;
-use crate::util::{into_syn_error, parse_meta_args_from_attrs, set_option_or_err, PathExt};
+use crate::util::{
+    convert_field_case, convert_ident_case, get_message_type, into_syn_error, parse_lit_case,
+    parse_lit_path, parse_lit_str, parse_meta_args_from_attrs, set_option_or_err, to_json_name,
+    PathExt,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ProstType {
@@ -12,42 +21,143 @@ pub enum ProstType {
     Oneof,
 }
 
+/// Naming of the values of an enumeration, given with `enum_style = "..."`.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnumStyle {
+    /// The name in the proto, e.g. `LANGUAGE_ENGLISH`.
+    #[default]
+    Proto,
+    /// The name without the prefix of the enum, e.g. `ENGLISH`.
+    StripPrefix,
+    /// The name without the prefix of the enum in lowercase, e.g. `english`.
+    StripPrefixLower,
+}
+
+impl EnumStyle {
+    fn parse(value: &Expr) -> Result<Self, syn::Error> {
+        match &parse_lit_str(value)?[..] {
+            "proto" => Ok(Self::Proto),
+            "strip_prefix" => Ok(Self::StripPrefix),
+            "strip_prefix_lower" => Ok(Self::StripPrefixLower),
+            _ => Err(into_syn_error(
+                value,
+                "unrecognized enum style, expected `\"proto\"`, `\"strip_prefix\"` or \
+                 `\"strip_prefix_lower\"`",
+            )),
+        }
+    }
+
+    /// Returns the name of a value, or `None` if it is the name in the proto.
+    ///
+    /// prost-build has already stripped the prefix from the idents of the variants, so the name is
+    /// the ident back in snake case.
+    pub fn get_name(self, ident: &Ident) -> Option<String> {
+        let case = match self {
+            Self::Proto => return None,
+            Self::StripPrefix => Case::ScreamingSnake,
+            Self::StripPrefixLower => Case::Snake,
+        };
+        Some(convert_ident_case(&ident.unraw().to_string(), case))
+    }
+}
+
+/// Alphabet and padding of base64 written for `bytes` fields, given with `base64_output = "..."`.
+/// Every variant is accepted on input regardless.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Base64Output {
+    #[default]
+    Standard,
+    StandardNoPad,
+    UrlSafe,
+    UrlSafeNoPad,
+}
+
+impl Base64Output {
+    fn parse(value: &Expr) -> Result<Self, syn::Error> {
+        match &parse_lit_str(value)?[..] {
+            "standard" => Ok(Self::Standard),
+            "standard_no_pad" => Ok(Self::StandardNoPad),
+            "url_safe" => Ok(Self::UrlSafe),
+            "url_safe_no_pad" => Ok(Self::UrlSafeNoPad),
+            _ => Err(into_syn_error(
+                value,
+                "unrecognized base64 output, expected `\"standard\"`, `\"standard_no_pad\"`, \
+                 `\"url_safe\"` or `\"url_safe_no_pad\"`",
+            )),
+        }
+    }
+
+    /// Returns the name of the runtime function writing the output.
+    pub fn get_encoder(self) -> Ident {
+        match self {
+            Self::Standard => format_ident!("encode_base64"),
+            Self::StandardNoPad => format_ident!("encode_base64_no_pad"),
+            Self::UrlSafe => format_ident!("encode_base64_url"),
+            Self::UrlSafeNoPad => format_ident!("encode_base64_url_no_pad"),
+        }
+    }
+}
+
 pub struct DeriveMeta {
     pub prost_type: ProstType,
+    pub crate_path: Path,
+    pub serde_path: Path,
     pub omit_type_errors: bool,
     pub use_default_for_missing_fields: bool,
     pub ignore_unknown_fields: bool,
+    pub rename_all: Option<Case>,
+    pub rename_all_variants: Option<Case>,
+    pub enum_style: EnumStyle,
+    pub lenient_enums: bool,
+    pub string_input: bool,
+    pub base64_output: Base64Output,
 }
 
 impl DeriveMeta {
-    fn from_attributes(attributes: &[Attribute]) -> Result<Self, syn::Error> {
+    pub fn from_ast(
+        context: &Context,
+        ident: &Ident,
+        attributes: &[Attribute],
+    ) -> Result<DeriveMeta, ()> {
         #[derive(Default)]
         pub struct DeriveMetaDefault {
-            pub prost_type: Option<ProstType>,
+            pub prost_type_candidates: Vec<(Path, ProstType)>,
+            pub prost_type_override: Option<ProstType>,
+            pub crate_path: Option<Path>,
+            pub serde_path: Option<Path>,
             pub omit_type_errors: bool,
             pub use_default_for_missing_fields: bool,
             pub ignore_unknown_fields: bool,
+            pub rename_all: Option<(Case, MetaNameValue)>,
+            pub rename_all_variants: Option<(Case, MetaNameValue)>,
+            pub enum_style: Option<(EnumStyle, MetaNameValue)>,
+            pub lenient_enums: Option<Path>,
+            pub string_input: Option<Path>,
+            pub base64_output: Option<(Base64Output, MetaNameValue)>,
         }
 
         let mut derive_meta = DeriveMetaDefault::default();
 
-        let ident_default_derive = format_ident!("derive");
         {
-            let meta_args = parse_meta_args_from_attrs(attributes, &ident_default_derive, true)?;
-
-            let prost_message: Path = parse_quote!(::prost::Message);
-            let prost_enumeration: Path = parse_quote!(::prost::Enumeration);
-            let prost_oneof: Path = parse_quote!(::prost::Oneof);
+            let ident_default_derive = format_ident!("derive");
+            let meta_args =
+                parse_meta_args_from_attrs(context, attributes, &ident_default_derive, true);
 
+            // prost derives can be referred through any path (e.g. `prost::Message`, `Message`
+            // after an import or a custom `prost_path` of prost-build), so the last segment
+            // decides the kind, and the candidates are narrowed down below
             for meta in meta_args {
                 if let Meta::Path(p) = &meta {
-                    if *p == prost_message {
-                        set_option_or_err(&mut derive_meta.prost_type, p, ProstType::Message)?;
-                    } else if *p == prost_enumeration {
-                        set_option_or_err(&mut derive_meta.prost_type, p, ProstType::Enum)?;
-                    } else if *p == prost_oneof {
-                        set_option_or_err(&mut derive_meta.prost_type, p, ProstType::Oneof)?;
-                    }
+                    let Some(segment) = p.segments.last() else {
+                        continue;
+                    };
+                    let ty = match &segment.ident.to_string()[..] {
+                        "Message" => ProstType::Message,
+                        "Enumeration" => ProstType::Enum,
+                        "Oneof" => ProstType::Oneof,
+                        _ => continue,
+                    };
+                    derive_meta.prost_type_candidates.push((p.clone(), ty));
                 }
             }
         }
@@ -57,54 +167,425 @@ impl DeriveMeta {
             let ident_use_default_for_missing_fields =
                 format_ident!("use_default_for_missing_fields");
             let ident_ignore_unknown_fields = format_ident!("ignore_unknown_fields");
-
-            let meta_args = parse_meta_args_from_attrs(attributes, &ident_derive, true)?;
+            let ident_message = format_ident!("message");
+            let ident_enumeration = format_ident!("enumeration");
+            let ident_oneof = format_ident!("oneof");
+            let ident_crate = format_ident!("crate");
+            let ident_serde = format_ident!("serde");
+            let ident_rename_all = format_ident!("rename_all");
+            let ident_rename_all_variants = format_ident!("rename_all_variants");
+            let ident_enum_style = format_ident!("enum_style");
+            let ident_lenient_enums = format_ident!("lenient_enums");
+            let ident_string_input = format_ident!("string_input");
+            let ident_base64_output = format_ident!("base64_output");
+
+            let meta_args = parse_meta_args_from_attrs(context, attributes, &ident_derive, true);
 
             for meta in meta_args {
-                if let Meta::Path(p) = &meta {
-                    if p.is_ident(&ident_omit_type_errors) {
+                match &meta {
+                    Meta::Path(p) if p.is_ident(&ident_message) => {
+                        context.ok_or_push(set_option_or_err(
+                            &mut derive_meta.prost_type_override,
+                            p,
+                            ProstType::Message,
+                        ));
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_enumeration) => {
+                        context.ok_or_push(set_option_or_err(
+                            &mut derive_meta.prost_type_override,
+                            p,
+                            ProstType::Enum,
+                        ));
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_oneof) => {
+                        context.ok_or_push(set_option_or_err(
+                            &mut derive_meta.prost_type_override,
+                            p,
+                            ProstType::Oneof,
+                        ));
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident(&ident_crate) => {
+                        if let Some(p) = context.ok_or_push(parse_lit_path(&nv.value)) {
+                            context.ok_or_push(set_option_or_err(
+                                &mut derive_meta.crate_path,
+                                nv,
+                                p,
+                            ));
+                        }
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident(&ident_serde) => {
+                        if let Some(p) = context.ok_or_push(parse_lit_path(&nv.value)) {
+                            context.ok_or_push(set_option_or_err(
+                                &mut derive_meta.serde_path,
+                                nv,
+                                p,
+                            ));
+                        }
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_omit_type_errors) => {
                         derive_meta.omit_type_errors = true;
-                    } else if p.is_ident(&ident_use_default_for_missing_fields) {
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_use_default_for_missing_fields) => {
                         derive_meta.use_default_for_missing_fields = true;
-                    } else if p.is_ident(&ident_ignore_unknown_fields) {
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_ignore_unknown_fields) => {
                         derive_meta.ignore_unknown_fields = true;
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident(&ident_rename_all) => {
+                        if let Some(case) = context.ok_or_push(parse_lit_case(&nv.value)) {
+                            context.ok_or_push(set_option_or_err(
+                                &mut derive_meta.rename_all,
+                                nv,
+                                (case, nv.clone()),
+                            ));
+                        }
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident(&ident_rename_all_variants) => {
+                        if let Some(case) = context.ok_or_push(parse_lit_case(&nv.value)) {
+                            context.ok_or_push(set_option_or_err(
+                                &mut derive_meta.rename_all_variants,
+                                nv,
+                                (case, nv.clone()),
+                            ));
+                        }
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident(&ident_enum_style) => {
+                        if let Some(style) = context.ok_or_push(EnumStyle::parse(&nv.value)) {
+                            context.ok_or_push(set_option_or_err(
+                                &mut derive_meta.enum_style,
+                                nv,
+                                (style, nv.clone()),
+                            ));
+                        }
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_lenient_enums) => {
+                        derive_meta.lenient_enums = Some(p.clone());
+                    }
+                    Meta::Path(p) if p.is_ident(&ident_string_input) => {
+                        derive_meta.string_input = Some(p.clone());
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident(&ident_base64_output) => {
+                        if let Some(output) = context.ok_or_push(Base64Output::parse(&nv.value)) {
+                            context.ok_or_push(set_option_or_err(
+                                &mut derive_meta.base64_output,
+                                nv,
+                                (output, nv.clone()),
+                            ));
+                        }
+                    }
+                    _ => context.push_error_spanned_by(
+                        meta,
+                        "unrecognized option, expected `message`, `enumeration`, `oneof`, \
+                         `crate = \"...\"`, `serde = \"...\"`, `omit_type_errors`, \
+                         `use_default_for_missing_fields`, `ignore_unknown_fields`, \
+                         `rename_all = \"...\"`, `rename_all_variants = \"...\"`, \
+                         `enum_style = \"...\"`, `lenient_enums`, `string_input` or \
+                         `base64_output = \"...\"`",
+                    ),
+                }
+            }
+        }
+
+        // an explicit type takes precedence over whatever has been derived. Otherwise the
+        // derives through a `prost` path win over derives of the same names from other crates
+        // (e.g. `actix::Message`), and bare names imported from prost come next.
+        let mut prost_type = derive_meta.prost_type_override;
+        if prost_type.is_none() {
+            let is_prost = |p: &Path| p.segments.iter().any(|segment| segment.ident == "prost");
+            let has_prost = derive_meta
+                .prost_type_candidates
+                .iter()
+                .any(|(p, _)| is_prost(p));
+            let candidates = derive_meta
+                .prost_type_candidates
+                .into_iter()
+                .filter(|(p, _)| {
+                    if has_prost {
+                        is_prost(p)
                     } else {
-                        return Err(into_syn_error(meta, "unrecognized option"));
+                        p.leading_colon.is_none() && p.segments.len() == 1
+                    }
+                });
+            for (p, ty) in candidates {
+                match prost_type {
+                    Some(prost_type) if prost_type != ty => {
+                        context.push_error_spanned_by(
+                            p,
+                            "ambiguous prost type, the derives are of different kinds, \
+                             specify it with `#[prost_serde_derive(message)]`, `(enumeration)` \
+                             or `(oneof)`",
+                        );
+                        return Err(());
                     }
-                } else {
-                    return Err(into_syn_error(meta, "unrecognized option"));
+                    _ => prost_type = Some(ty),
                 }
             }
         }
 
+        let Some(prost_type) = prost_type else {
+            context.push_error_spanned_by(
+                ident,
+                "missing prost type, expected `#[derive(prost::Message)]`, \
+                 `#[derive(prost::Enumeration)]` or `#[derive(prost::Oneof)]`, \
+                 or specify it with `#[prost_serde_derive(message)]`, `(enumeration)` or `(oneof)`",
+            );
+            return Err(());
+        };
+
+        // the options are only meaningful to a single kind of types
+        if let Some((_, nv)) = &derive_meta.rename_all {
+            if prost_type != ProstType::Message {
+                context.push_error_spanned_by(nv, "`rename_all` is only available on messages");
+            }
+        }
+        if let Some((_, nv)) = &derive_meta.rename_all_variants {
+            if prost_type != ProstType::Oneof {
+                context
+                    .push_error_spanned_by(nv, "`rename_all_variants` is only available on oneofs");
+            }
+        }
+        if let Some((_, nv)) = &derive_meta.enum_style {
+            if prost_type != ProstType::Enum {
+                context.push_error_spanned_by(nv, "`enum_style` is only available on enumerations");
+            }
+        }
+        if let Some(p) = &derive_meta.lenient_enums {
+            if prost_type == ProstType::Oneof {
+                context.push_error_spanned_by(
+                    p,
+                    "`lenient_enums` is only available on messages and enumerations",
+                );
+            }
+        }
+        if let Some(p) = &derive_meta.string_input {
+            if prost_type != ProstType::Message {
+                context.push_error_spanned_by(p, "`string_input` is only available on messages");
+            }
+        }
+        if let Some((_, nv)) = &derive_meta.base64_output {
+            if prost_type != ProstType::Message {
+                context.push_error_spanned_by(nv, "`base64_output` is only available on messages");
+            }
+        }
+
         Ok(DeriveMeta {
-            prost_type: derive_meta.prost_type.ok_or_else(|| {
-                into_syn_error(
-                    &ident_default_derive,
-                    "missing prost type(::prost:Message, ::prost::Enumeration, or ::prost::Oneof)",
-                )
-            })?,
+            prost_type,
+            crate_path: derive_meta
+                .crate_path
+                .unwrap_or_else(|| parse_quote!(::prost_serde_derive)),
+            serde_path: derive_meta
+                .serde_path
+                .unwrap_or_else(|| parse_quote!(::serde)),
             omit_type_errors: derive_meta.omit_type_errors,
             use_default_for_missing_fields: derive_meta.use_default_for_missing_fields,
             ignore_unknown_fields: derive_meta.ignore_unknown_fields,
+            rename_all: derive_meta.rename_all.map(|(case, _)| case),
+            rename_all_variants: derive_meta.rename_all_variants.map(|(case, _)| case),
+            enum_style: derive_meta
+                .enum_style
+                .map(|(style, _)| style)
+                .unwrap_or_default(),
+            lenient_enums: derive_meta.lenient_enums.is_some(),
+            string_input: derive_meta.string_input.is_some(),
+            base64_output: derive_meta
+                .base64_output
+                .map(|(output, _)| output)
+                .unwrap_or_default(),
         })
     }
 
-    pub fn from_ast(context: &Context, attributes: &[Attribute]) -> Result<DeriveMeta, ()> {
-        match Self::from_attributes(attributes) {
-            Ok(v) => Ok(v),
-            Err(e) => {
-                context.push_syn_error(e);
-                Err(())
-            }
+    /// Returns the key of a member of a oneof, which is its name in the proto converted by
+    /// `rename_all_variants`.
+    pub fn get_variant_name(&self, ident: &Ident) -> String {
+        let name = ident.unraw().to_string().to_case(Case::Snake);
+        match self.rename_all_variants {
+            Some(case) => convert_field_case(&name, case),
+            None => name,
         }
     }
 }
 
-#[derive(Clone, Copy)]
-pub enum ProstBytesType {
-    Bytes,
-    Vec,
+/// Representation of a `bytes` field in human-readable formats, given with
+/// `bytes_encoding = "..."`.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum BytesEncoding {
+    /// Base64 written as `base64_output` of the message.
+    #[default]
+    Base64,
+    /// Base64 written in the URL-safe alphabet without padding.
+    Base64Url,
+    /// Lowercase hex.
+    Hex,
+    /// An array of numbers.
+    Array,
+}
+
+impl BytesEncoding {
+    fn parse(value: &Expr) -> Result<Self, syn::Error> {
+        match &parse_lit_str(value)?[..] {
+            "base64" => Ok(Self::Base64),
+            "base64url" => Ok(Self::Base64Url),
+            "hex" => Ok(Self::Hex),
+            "array" => Ok(Self::Array),
+            _ => Err(into_syn_error(
+                value,
+                "unrecognized bytes encoding, expected `\"base64\"`, `\"base64url\"`, \
+                 `\"hex\"` or `\"array\"`",
+            )),
+        }
+    }
+}
+
+/// Options of a single field, given with `#[prost_serde_derive(...)]` on the field, e.g. by
+/// `field_attribute()` of prost-build.
+#[derive(Default)]
+pub struct FieldMeta {
+    pub rename: Option<String>,
+    pub aliases: Vec<String>,
+    pub skip_serializing_if_default: bool,
+    pub default: Option<Path>,
+    pub omit_type_errors: bool,
+    pub bytes_encoding: BytesEncoding,
+}
+
+impl FieldMeta {
+    pub fn from_ast(
+        context: &Context,
+        derive_meta: &DeriveMeta,
+        ident: &Ident,
+        prost_attr: &ProstAttr,
+        attributes: &[Attribute],
+    ) -> Self {
+        let ident_derive = format_ident!("prost_serde_derive");
+        let ident_rename = format_ident!("rename");
+        let ident_alias = format_ident!("alias");
+        let ident_skip_serializing_if_default = format_ident!("skip_serializing_if_default");
+        let ident_default = format_ident!("default");
+        let ident_omit_type_errors = format_ident!("omit_type_errors");
+        let ident_bytes_encoding = format_ident!("bytes_encoding");
+
+        let mut field_meta = FieldMeta::default();
+        let mut bytes_encoding = None;
+        let is_oneof = matches!(prost_attr.ty, ProtobufType::OneOf(_));
+        // members of a oneof are keyed by `rename_all_variants` and are `None` when missing
+        let is_member = derive_meta.prost_type == ProstType::Oneof;
+
+        let meta_args = parse_meta_args_from_attrs(context, attributes, &ident_derive, true);
+        for meta in meta_args {
+            match &meta {
+                Meta::NameValue(nv) if is_member && !nv.path.is_ident(&ident_bytes_encoding) => {
+                    context.push_error_spanned_by(
+                        nv,
+                        "only `bytes_encoding` is available on oneof members",
+                    );
+                }
+                Meta::Path(p) if is_member => {
+                    context.push_error_spanned_by(
+                        p,
+                        "only `bytes_encoding` is available on oneof members",
+                    );
+                }
+                Meta::NameValue(nv) if nv.path.is_ident(&ident_rename) => {
+                    if is_oneof {
+                        context.push_error_spanned_by(
+                            nv,
+                            "oneof fields are keyed by the names of their members, \
+                             `rename` is not available",
+                        );
+                    } else if let Some(name) = context.ok_or_push(parse_lit_str(&nv.value)) {
+                        if name.is_empty() {
+                            context.push_error_spanned_by(nv, "`rename` must not be empty");
+                        } else {
+                            context.ok_or_push(set_option_or_err(&mut field_meta.rename, nv, name));
+                        }
+                    }
+                }
+                Meta::NameValue(nv) if nv.path.is_ident(&ident_alias) => {
+                    if is_oneof {
+                        context.push_error_spanned_by(
+                            nv,
+                            "oneof fields are keyed by the names of their members, \
+                             `alias` is not available",
+                        );
+                    } else if let Some(name) = context.ok_or_push(parse_lit_str(&nv.value)) {
+                        if name.is_empty() {
+                            context.push_error_spanned_by(nv, "`alias` must not be empty");
+                        } else {
+                            field_meta.aliases.push(name);
+                        }
+                    }
+                }
+                Meta::Path(p) if p.is_ident(&ident_skip_serializing_if_default) => {
+                    field_meta.skip_serializing_if_default = true;
+                }
+                Meta::NameValue(nv) if nv.path.is_ident(&ident_default) => {
+                    if is_oneof || matches!(prost_attr.modifier, FieldModifier::Optional) {
+                        context.push_error_spanned_by(
+                            nv,
+                            "optional and oneof fields are `None` when missing, \
+                             `default` is not available",
+                        );
+                    } else if let Some(p) = context.ok_or_push(parse_lit_path(&nv.value)) {
+                        context.ok_or_push(set_option_or_err(&mut field_meta.default, nv, p));
+                    }
+                }
+                Meta::Path(p) if p.is_ident(&ident_omit_type_errors) => {
+                    field_meta.omit_type_errors = true;
+                }
+                Meta::NameValue(nv) if nv.path.is_ident(&ident_bytes_encoding) => {
+                    if !matches!(prost_attr.ty, ProtobufType::Bytes) {
+                        context.push_error_spanned_by(
+                            nv,
+                            "`bytes_encoding` is only available on bytes fields",
+                        );
+                    } else if let Some(encoding) =
+                        context.ok_or_push(BytesEncoding::parse(&nv.value))
+                    {
+                        context.ok_or_push(set_option_or_err(&mut bytes_encoding, nv, encoding));
+                    }
+                }
+                _ => context.push_error_spanned_by(
+                    meta,
+                    "unrecognized field option, expected `rename = \"...\"`, \
+                     `alias = \"...\"`, `skip_serializing_if_default`, `default = \"...\"`, \
+                     `omit_type_errors` or `bytes_encoding = \"...\"`",
+                ),
+            }
+        }
+
+        field_meta.bytes_encoding = bytes_encoding.unwrap_or_default();
+
+        // `rename_all` renames every field which is not renamed on its own
+        if field_meta.rename.is_none() {
+            field_meta.rename = derive_meta
+                .rename_all
+                .map(|case| convert_field_case(&ident.unraw().to_string(), case));
+        }
+
+        field_meta
+    }
+
+    /// Returns the key of the field, which is the name in the proto unless it is renamed.
+    pub fn get_name(&self, ident: &Ident) -> String {
+        match &self.rename {
+            Some(name) => name.clone(),
+            None => ident.unraw().to_string(),
+        }
+    }
+
+    /// Returns the names accepted for the field on top of its key: the aliases, and the JSON name
+    /// unless the field is renamed.
+    pub fn get_alternative_names(&self, ident: &Ident) -> Vec<String> {
+        let mut names = self.aliases.clone();
+        if self.rename.is_none() {
+            let name = ident.unraw().to_string();
+            let json_name = to_json_name(&name);
+            if json_name != name {
+                names.push(json_name);
+            }
+        }
+        names
+    }
 }
 
 #[derive(Clone)]
@@ -113,7 +594,7 @@ pub enum ProtobufType {
     Enumeration(Path),
     Bool,
     String,
-    Bytes(ProstBytesType),
+    Bytes,
     Int32,
     Fixed32,
     Uint32,
@@ -125,6 +606,23 @@ pub enum ProtobufType {
     OneOf(Path),
 }
 
+impl ProtobufType {
+    /// Returns whether the type is an integer or floating point type.
+    pub fn is_number(&self) -> bool {
+        matches!(
+            self,
+            Self::Int32
+                | Self::Fixed32
+                | Self::Uint32
+                | Self::Int64
+                | Self::Fixed64
+                | Self::Uint64
+                | Self::Float
+                | Self::Double
+        )
+    }
+}
+
 impl TryFrom<&Meta> for ProtobufType {
     type Error = syn::Error;
 
@@ -142,14 +640,19 @@ impl TryFrom<&Meta> for ProtobufType {
 
                 let ident = nv.path.get_ident_or_err()?;
 
+                let parse_path = || {
+                    syn::parse_str(&value_literal).map_err(|_| {
+                        into_syn_error(&nv.value, "should be a path to the type, e.g. `\"Type\"`")
+                    })
+                };
+
                 match &ident.to_string()[..] {
-                    "enumeration" => Ok(Self::Enumeration(syn::parse_str(&value_literal)?)),
+                    "enumeration" => Ok(Self::Enumeration(parse_path()?)),
                     "bytes" => match &value_literal[..] {
-                        "bytes" => Ok(Self::Bytes(ProstBytesType::Bytes)),
-                        "vec" => Ok(Self::Bytes(ProstBytesType::Vec)),
+                        "bytes" | "vec" => Ok(Self::Bytes),
                         _ => Err(into_syn_error(&nv.value, "should be `bytes` or `vec`")),
                     },
-                    "oneof" => Ok(Self::OneOf(syn::parse_str(&value_literal)?)),
+                    "oneof" => Ok(Self::OneOf(parse_path()?)),
                     _ => Err(into_syn_error(ident, "unrecognized type")),
                 }
             }
@@ -158,12 +661,14 @@ impl TryFrom<&Meta> for ProtobufType {
 
                 match &ident.to_string()[..] {
                     "string" => Ok(ProtobufType::String),
+                    // prost omits the representation of `bytes` inside oneofs
+                    "bytes" => Ok(ProtobufType::Bytes),
                     "message" => Ok(ProtobufType::Message),
                     "bool" => Ok(ProtobufType::Bool),
-                    "int32" => Ok(ProtobufType::Int32),
+                    "int32" | "sint32" => Ok(ProtobufType::Int32),
                     "fixed32" | "sfixed32" => Ok(ProtobufType::Fixed32),
                     "uint32" => Ok(ProtobufType::Uint32),
-                    "int64" => Ok(ProtobufType::Int64),
+                    "int64" | "sint64" => Ok(ProtobufType::Int64),
                     "fixed64" | "sfixed64" => Ok(ProtobufType::Fixed64),
                     "uint64" => Ok(ProtobufType::Uint64),
                     "float" => Ok(ProtobufType::Float),
@@ -202,100 +707,96 @@ impl TryFrom<&Meta> for FieldModifier {
     }
 }
 
-#[derive(Clone)]
-pub enum Tag {
-    Tag(i32),
-    OneofTag(Vec<i32>),
-}
-
-impl TryFrom<&Meta> for Tag {
-    type Error = syn::Error;
-
-    fn try_from(value: &Meta) -> Result<Self, Self::Error> {
-        if let Meta::NameValue(nv) = value {
-            if nv.path.is_ident("tag") {
-                let value_literal = match &nv.value {
-                    Expr::Lit(ExprLit {
-                        lit: Lit::Str(lit), ..
-                    }) => lit.value(),
-                    _ => {
-                        return Err(into_syn_error(&nv.value, "should be a string literal"));
-                    }
-                };
-
-                let Ok(tag) = value_literal.parse::<i32>() else {
-                    return Err(into_syn_error(&nv.value, "invalid tag value"));
-                };
-
-                Ok(Self::Tag(tag))
-            } else if nv.path.is_ident("tags") {
-                let value_literal = match &nv.value {
-                    Expr::Lit(ExprLit {
-                        lit: Lit::Str(lit), ..
-                    }) => lit.value(),
-                    _ => {
-                        return Err(into_syn_error(&nv.value, "should be a string literal"));
-                    }
-                };
-
-                let Ok(tags) = value_literal.split(", ").map(str::parse::<i32>).collect() else {
-                    return Err(into_syn_error(&nv.value, "invalid tag values"));
-                };
-
-                Ok(Self::OneofTag(tags))
-            } else {
-                Err(into_syn_error(&nv.path, "invalid directive"))
-            }
-        } else {
-            Err(into_syn_error(value, "invalid directive"))
-        }
+/// Returns whether the meta is a well-formed `tag = "1"` or `tags = "1, 2"` directive.
+fn is_tag(value: &Meta) -> bool {
+    let Meta::NameValue(nv) = value else {
+        return false;
+    };
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit), ..
+    }) = &nv.value
+    else {
+        return false;
+    };
+    let value_literal = lit.value();
+
+    if nv.path.is_ident("tag") {
+        value_literal.parse::<i32>().is_ok()
+    } else if nv.path.is_ident("tags") {
+        value_literal
+            .split(", ")
+            .all(|tag| tag.parse::<i32>().is_ok())
+    } else {
+        false
     }
 }
 
 pub struct ProstAttr {
     pub ty: ProtobufType,
     pub modifier: FieldModifier,
-    pub tag: Tag,
 }
 
 impl ProstAttr {
-    fn from_attributes(attrs: &[Attribute]) -> Result<Self, syn::Error> {
+    pub fn from_ast<A: ToTokens>(
+        context: &Context,
+        obj: A,
+        attrs: &[Attribute],
+    ) -> Result<Self, ()> {
         let ident_prost = format_ident!("prost");
-        let ident_tag = format_ident!("tag");
 
-        let meta_args = parse_meta_args_from_attrs(attrs, &ident_prost, false)?;
+        let meta_args = parse_meta_args_from_attrs(context, attrs, &ident_prost, false);
 
         let mut ty = None;
         let mut modifier = None;
         let mut tag = None;
 
         for meta in meta_args {
-            if let Ok(t) = ProtobufType::try_from(&meta) {
-                set_option_or_err(&mut ty, meta, t)?;
+            let result = if let Ok(t) = ProtobufType::try_from(&meta) {
+                set_option_or_err(&mut ty, &meta, t)
             } else if let Ok(m) = FieldModifier::try_from(&meta) {
-                set_option_or_err(&mut modifier, meta, m)?;
-            } else if let Ok(t) = Tag::try_from(&meta) {
-                set_option_or_err(&mut tag, meta, t)?;
-            }
+                set_option_or_err(&mut modifier, &meta, m)
+            } else if is_tag(&meta) {
+                set_option_or_err(&mut tag, &meta, ())
+            } else if ["enumeration", "bytes", "oneof"]
+                .iter()
+                .any(|v| meta.path().is_ident(v))
+            {
+                // a known type with a malformed value, report why it has been rejected
+                ProtobufType::try_from(&meta).map(|_| ())
+            } else {
+                // other prost options(e.g. `packed`, `default`) are irrelevant here
+                Ok(())
+            };
+            context.ok_or_push(result);
+        }
+
+        if ty.is_none() {
+            context.push_error_spanned_by(
+                &obj,
+                "missing prost type, expected an attribute like `#[prost(string, tag = \"1\")]`",
+            );
+        }
+        if tag.is_none() {
+            context.push_error_spanned_by(
+                &obj,
+                match ty {
+                    Some(ProtobufType::OneOf(_)) => {
+                        "missing prost tags, expected an attribute like \
+                         `#[prost(oneof = \"Type\", tags = \"1, 2\")]`"
+                    }
+                    _ => "missing prost tag, expected an attribute like `#[prost(string, tag = \"1\")]`",
+                },
+            );
         }
 
+        tag.ok_or(())?;
+
         Ok(Self {
-            ty: ty.ok_or_else(|| into_syn_error(&ident_prost, "missing type"))?,
+            ty: ty.ok_or(())?,
             modifier: modifier.unwrap_or_default(),
-            tag: tag.ok_or_else(|| into_syn_error(&ident_tag, "missing tag"))?,
         })
     }
 
-    pub fn from_ast(context: &Context, attrs: &[Attribute]) -> Result<Self, ()> {
-        match Self::from_attributes(attrs) {
-            Ok(v) => Ok(v),
-            Err(e) => {
-                context.push_syn_error(e);
-                Err(())
-            }
-        }
-    }
-
     pub fn get_default_value(&self) -> TokenStream {
         match self.modifier {
             FieldModifier::None => match &self.ty {
@@ -305,4 +806,24 @@ impl ProstAttr {
             _ => quote! { Default::default() },
         }
     }
+
+    /// Bounds the message type of the field by `assert_trait`, for the where clauses of the impls
+    /// reading or writing the field. A missing derive is then reported once instead of at every
+    /// use of the field in the generated code.
+    pub fn get_field_bound(
+        &self,
+        ty: &Type,
+        assert_trait: &Ident,
+        lifetime: &Lifetime,
+    ) -> Option<TokenStream> {
+        match self.ty {
+            ProtobufType::Message | ProtobufType::OneOf(_) => {
+                let ty = get_message_type(ty);
+                Some(quote_spanned! {ty.span()=>
+                    #ty: _prost_serde_derive::__private::#assert_trait<#lifetime>
+                })
+            }
+            _ => None,
+        }
+    }
 }
@@ -28,6 +28,16 @@ impl Context {
         self.errors.borrow_mut().as_mut().unwrap().push(err);
     }
 
+    pub fn ok_or_push<T>(&self, result: Result<T, syn::Error>) -> Option<T> {
+        match result {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.push_syn_error(e);
+                None
+            }
+        }
+    }
+
     pub fn check(self) -> Result<(), Vec<syn::Error>> {
         let errors = self.errors.borrow_mut().take().unwrap();
         match errors.len() {
@@ -6,16 +6,23 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{parse_quote, Data, DeriveInput, Error, Path};
 
-use self::r#enum::{expand_enum, expand_oneof_field_names_method};
+use self::r#enum::{expand_enum, expand_enum_style_impl, expand_oneof_methods};
 use self::r#struct::expand_struct;
 use crate::attr::{DeriveMeta, ProstType};
 use crate::context::Context;
 use crate::util::wrap_block;
 
+pub struct DeserializeTokenStream {
+    pub items: TokenStream,
+    pub deserialize_block: TokenStream,
+    pub deserialize_in_place_block: Option<TokenStream>,
+    pub where_clause: TokenStream,
+}
+
 pub fn expand_deserialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>> {
     let context = Context::new();
 
-    let Ok(derive_meta) = DeriveMeta::from_ast(&context, &input.attrs) else {
+    let Ok(derive_meta) = DeriveMeta::from_ast(&context, &input.ident, &input.attrs) else {
         context.check()?;
         unreachable!()
     };
@@ -26,7 +33,12 @@ pub fn expand_deserialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>>
     let serde: Path = parse_quote! { _serde };
     let deserializer = format_ident!("deserializer");
 
-    let Ok(deserialization_block) = (match data {
+    let Ok(DeserializeTokenStream {
+        items,
+        deserialize_block,
+        deserialize_in_place_block,
+        where_clause,
+    }) = (match data {
         Data::Struct(d) => {
             if derive_meta.prost_type == ProstType::Message {
                 expand_struct(&context, &derive_meta, &serde, &deserializer, ident, d)
@@ -42,7 +54,14 @@ pub fn expand_deserialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>>
             if derive_meta.prost_type == ProstType::Enum
                 || derive_meta.prost_type == ProstType::Oneof
             {
-                expand_enum(&context, &derive_meta, &serde, &deserializer, ident, d)
+                expand_enum(&context, &derive_meta, &serde, &deserializer, ident, d).map(
+                    |deserialize_block| DeserializeTokenStream {
+                        items: quote! {},
+                        deserialize_block,
+                        deserialize_in_place_block: None,
+                        where_clause: quote! {},
+                    },
+                )
             } else {
                 context.push_error_spanned_by(
                     d.enum_token,
@@ -58,26 +77,51 @@ pub fn expand_deserialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>>
             );
             Err(())
         }
-    }) else {
+    })
+    else {
         context.check()?;
         unreachable!();
     };
 
-    let oneof_field_names_method = expand_oneof_field_names_method(&derive_meta, ident, data);
+    let oneof_methods = expand_oneof_methods(&context, &derive_meta, &serde, ident, data);
+    let enum_style_impl = expand_enum_style_impl(&derive_meta, ident, data);
 
+    let deserialize_in_place_fn = deserialize_in_place_block.map(|block| {
+        quote! {
+            fn deserialize_in_place<D>(#deserializer: D, place: &mut Self) -> Result<(), D::Error>
+            where D: #serde::Deserializer<'de>,
+            {
+                #block
+            }
+        }
+    });
+
+    let crate_path = &derive_meta.crate_path;
+    let serde_path = &derive_meta.serde_path;
     let impl_body = quote! {
-        extern crate serde as _serde;
+        use #serde_path as _serde;
+        #[allow(unused_imports)]
+        use #crate_path as _prost_serde_derive;
+
+        #items
 
-        impl<'de> #serde::Deserialize<'de> for #ident {
+        impl<'de> #serde::Deserialize<'de> for #ident #where_clause {
             fn deserialize<D>(#deserializer: D) -> Result<#ident, D::Error>
             where D: #serde::Deserializer<'de>,
             {
-                #deserialization_block
+                #deserialize_block
             }
+
+            #deserialize_in_place_fn
         }
 
-        #oneof_field_names_method
+        impl<'de> _prost_serde_derive::__private::DeserializeMessage<'de> for #ident {}
+
+        #oneof_methods
+        #enum_style_impl
     };
 
+    context.check()?;
+
     Ok(wrap_block(impl_body))
 }
@@ -1,14 +1,14 @@
 use std::iter;
 
-use convert_case::{Case, Casing};
 use itertools::Itertools;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use syn::ext::IdentExt;
 use syn::{Data, DataEnum, Path};
 
-use crate::attr::{DeriveMeta, ProstAttr, ProstType};
+use crate::attr::{DeriveMeta, FieldMeta, ProstAttr, ProstType, ProtobufType};
 use crate::context::Context;
+use crate::deserialize::field::{get_bytes_seed, get_enum_seed};
+use crate::util::{expand_name_match, get_enum_prefix, to_json_name};
 
 pub fn expand_enum(
     context: &Context,
@@ -18,11 +18,83 @@ pub fn expand_enum(
     ident: &Ident,
     d: &DataEnum,
 ) -> Result<TokenStream, ()> {
+    let expecting = format!("enum {}", ident);
+
     match meta.prost_type {
-        ProstType::Enum => Ok(quote! {
-            let s = String::deserialize(#deserializer)?;
-            #ident::from_str_name(&s).ok_or(#serde::de::Error::unknown_variant(&s, &[]))
-        }),
+        ProstType::Enum => {
+            let value = match get_styled_value_match(meta, ident, d, quote! { value }) {
+                Some(value_match) => value_match,
+                None => quote! { #ident::from_str_name(value) },
+            };
+            let prefix = get_enum_prefix(ident);
+            let lenient_enums = meta.lenient_enums;
+            Ok(quote! {
+            struct Visitor;
+
+            impl<'de> #serde::de::Visitor<'de> for Visitor {
+                type Value = #ident;
+
+                fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    formatter.write_str(#expecting)
+                }
+
+                fn visit_str<E>(self, value: &str) -> Result<#ident, E>
+                where
+                    E: #serde::de::Error,
+                {
+                    if let Some(v) = #value {
+                        return Ok(v);
+                    }
+                    if _prost_serde_derive::__private::lenient_enums(#lenient_enums) {
+                        let v = _prost_serde_derive::__private::parse_enum_leniently(
+                            value,
+                            #prefix,
+                            |name| #ident::from_str_name(name).map(|v| v as i32),
+                        )?;
+                        if let Some(v) = v.and_then(|v| <#ident as ::core::convert::TryFrom<i32>>::try_from(v).ok()) {
+                            return Ok(v);
+                        }
+                    }
+                    Err(#serde::de::Error::unknown_variant(value, &[]))
+                }
+
+                fn visit_i64<E>(self, value: i64) -> Result<#ident, E>
+                where
+                    E: #serde::de::Error,
+                {
+                    i32::try_from(value).ok().and_then(|v| <#ident as ::core::convert::TryFrom<i32>>::try_from(v).ok()).ok_or_else(|| {
+                        #serde::de::Error::invalid_value(
+                            #serde::de::Unexpected::Signed(value),
+                            &"a valid enum value",
+                        )
+                    })
+                }
+
+                fn visit_u64<E>(self, value: u64) -> Result<#ident, E>
+                where
+                    E: #serde::de::Error,
+                {
+                    i32::try_from(value).ok().and_then(|v| <#ident as ::core::convert::TryFrom<i32>>::try_from(v).ok()).ok_or_else(|| {
+                        #serde::de::Error::invalid_value(
+                            #serde::de::Unexpected::Unsigned(value),
+                            &"a valid enum value",
+                        )
+                    })
+                }
+            }
+
+            // names, and numbers as the protobuf JSON mapping allows
+            if #deserializer.is_human_readable() {
+                #deserializer.deserialize_any(Visitor)
+            } else {
+                let v = <i32 as #serde::Deserialize>::deserialize(#deserializer)?;
+                <#ident as ::core::convert::TryFrom<i32>>::try_from(v).ok().ok_or_else(|| #serde::de::Error::invalid_value(
+                    #serde::de::Unexpected::Signed(v as i64),
+                    &"a valid enum value",
+                ))
+            }
+            })
+        }
         ProstType::Oneof => OneofDeserializer::new(
             context,
             meta,
@@ -61,13 +133,18 @@ impl<'a> OneofDeserializer<'a> {
         ident: &'a Ident,
         variants: Vec<&syn::Variant>,
     ) -> Result<Self, ()> {
-        let mut typed_variants = Vec::new();
-        for variant in variants {
-            let attr = ProstAttr::from_ast(context, &variant.attrs)?;
-            typed_variants.push(Variant {
-                ident: variant.ident.clone(),
-                attr,
-            });
+        // parse every variant before bailing out so that all of the errors are reported at once
+        let (typed_variants, errors): (Vec<_>, Vec<()>) = variants
+            .into_iter()
+            .map(|variant| {
+                ProstAttr::from_ast(context, &variant.ident, &variant.attrs).map(|attr| Variant {
+                    ident: variant.ident.clone(),
+                    attr,
+                })
+            })
+            .partition_result();
+        if !errors.is_empty() {
+            return Err(());
         }
 
         Ok(Self {
@@ -92,17 +169,22 @@ impl<'a> OneofDeserializer<'a> {
 
         let names = variants
             .iter()
-            .map(|v| v.to_string().to_case(Case::Snake))
+            .map(|v| self.meta.get_variant_name(v))
             .collect_vec();
 
         let ident_enum = format_ident!("Variant");
         let ident_visitor = format_ident!("{}Visitor", ident_enum);
 
         let mut match_arms = Vec::new();
-        for (variant, name) in iter::zip(variants.iter(), names.iter()) {
+        let mut index_match_arms = Vec::new();
+        for (index, (variant, name)) in iter::zip(variants.iter(), names.iter()).enumerate() {
+            let index = index as u64;
             match_arms.push(quote! {
                 #name => Ok(#ident_enum::#variant)
-            })
+            });
+            index_match_arms.push(quote! {
+                #index => Ok(#ident_enum::#variant)
+            });
         }
 
         let expecting_names = names.iter().map(|v| format!("`{}`", v)).join(" or ");
@@ -134,6 +216,19 @@ impl<'a> OneofDeserializer<'a> {
                                 _ => Err(#serde::de::Error::unknown_field(value, VARIANTS))
                             }
                         }
+
+                        fn visit_u64<E>(self, value: u64) -> Result<#ident_enum, E>
+                        where
+                            E: #serde::de::Error,
+                        {
+                            match value {
+                                #(#index_match_arms),*,
+                                _ => Err(#serde::de::Error::invalid_value(
+                                    #serde::de::Unexpected::Unsigned(value),
+                                    &#expecting_names,
+                                )),
+                            }
+                        }
                     }
 
                     deserializer.deserialize_identifier(#ident_visitor)
@@ -151,16 +246,26 @@ impl<'a> OneofDeserializer<'a> {
         let expecting = format!("enum {}", ident_self);
         let ident_visitor = format_ident!("Visitor");
 
-        let (ident_variant_enum, variant_deserializer, variants) =
-            self.expand_variant_deserializer_impl();
+        let (ident_variant_enum, variant_deserializer, _) = self.expand_variant_deserializer_impl();
 
         let mut variant_match_arms = Vec::new();
 
-        for variant in variants {
+        for Variant { ident, attr } in &self.variants {
+            // bytes are native byte strings in non-human-readable formats
+            let value = match attr.ty {
+                ProtobufType::Bytes => quote! {
+                    #serde::de::VariantAccess::newtype_variant::<
+                        _prost_serde_derive::__private::DeserializeBytes,
+                    >(variant)?.0.into()
+                },
+                _ => quote! {
+                    #serde::de::VariantAccess::newtype_variant(variant)?
+                },
+            };
             variant_match_arms.push(quote! {
-                (#ident_variant_enum::#variant, variant) => {
-                    let value = #serde::de::VariantAccess::newtype_variant(variant)?;
-                    Ok(#ident_self::#variant(value))
+                (#ident_variant_enum::#ident, variant) => {
+                    let value = #value;
+                    Ok(#ident_self::#ident(value))
                 }
             });
         }
@@ -198,8 +303,7 @@ impl<'a> OneofDeserializer<'a> {
         let name = self.ident.to_string();
         let fields = self
             .get_variant_idents()
-            .map(ToString::to_string)
-            .map(|s| s.to_case(Case::Snake))
+            .map(|v| self.meta.get_variant_name(v))
             .collect_vec();
 
         let (visitor_ident, visitor_impl) = self.expand_visitor_impl()?;
@@ -213,23 +317,173 @@ impl<'a> OneofDeserializer<'a> {
     }
 }
 
-pub fn expand_oneof_field_names_method(
+/// Returns a match of the string `value` to the values of the enum by their names in the style of
+/// the enum, or `None` if the enum keeps the names in the proto.
+fn get_styled_value_match(
+    derive_meta: &DeriveMeta,
+    ident: &Ident,
+    data: &DataEnum,
+    value: TokenStream,
+) -> Option<TokenStream> {
+    let match_arms = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let ident_variant = &variant.ident;
+            let name = derive_meta.enum_style.get_name(ident_variant)?;
+            Some(quote! { #name => Some(#ident::#ident_variant), })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(quote! {
+        match #value {
+            #(#match_arms)*
+            _ => None,
+        }
+    })
+}
+
+/// Lets fields of the enum, which only know its path, read the names in its style.
+pub fn expand_enum_style_impl(derive_meta: &DeriveMeta, ident: &Ident, data: &Data) -> TokenStream {
+    if let Data::Enum(d) = data {
+        if derive_meta.prost_type == ProstType::Enum {
+            if let Some(value_match) =
+                get_styled_value_match(derive_meta, ident, d, quote! { name })
+            {
+                return quote! {
+                    impl _prost_serde_derive::__private::StyledEnumValues for #ident {
+                        fn value(name: &str) -> Option<i32> {
+                            #value_match.map(|v| v as i32)
+                        }
+                    }
+                };
+            }
+        }
+    }
+
+    quote! {}
+}
+
+pub fn expand_oneof_methods(
+    context: &Context,
     derive_meta: &DeriveMeta,
+    serde: &Path,
     ident: &Ident,
     data: &Data,
 ) -> TokenStream {
     if let Data::Enum(d) = data {
         if derive_meta.prost_type == ProstType::Oneof {
-            let variants = d
-                .variants
+            let variants = d.variants.iter().map(|v| &v.ident).collect_vec();
+            let names = variants
                 .iter()
-                .map(|v| v.ident.unraw().to_string().to_case(Case::Snake))
+                .map(|v| derive_meta.get_variant_name(v))
                 .collect_vec();
-
+            // the members are read by the seeds of fields of the same types, with the options of
+            // the message
+            let mut member_exprs = Vec::new();
+            for variant in &d.variants {
+                let ident_variant = &variant.ident;
+                let Ok(prost_attr) = ProstAttr::from_ast(context, ident_variant, &variant.attrs)
+                else {
+                    return quote! {};
+                };
+                let field_meta = FieldMeta::from_ast(
+                    context,
+                    derive_meta,
+                    ident_variant,
+                    &prost_attr,
+                    &variant.attrs,
+                );
+                member_exprs.push(match prost_attr.ty {
+                    ProtobufType::Enumeration(ref path) => {
+                        let seed = get_enum_seed(
+                            path,
+                            quote! { options.lenient_enums },
+                            quote! { options.ignore_unknown_fields },
+                        );
+                        quote! {
+                            #serde::de::DeserializeSeed::deserialize(#seed, deserializer)
+                                .map(|v| v.map(#ident::#ident_variant))
+                        }
+                    }
+                    // bytes are read as `Vec<u8>`, which may be stored as `Bytes`
+                    ProtobufType::Bytes => {
+                        let seed = get_bytes_seed(field_meta.bytes_encoding);
+                        quote! {
+                            #serde::de::DeserializeSeed::deserialize(#seed, deserializer)
+                                .map(|v| Some(#ident::#ident_variant(v.into())))
+                        }
+                    }
+                    ref ty if ty.is_number() => quote! {
+                        #serde::de::DeserializeSeed::deserialize(
+                            _prost_serde_derive::__private::NumberSeed::new(),
+                            deserializer,
+                        )
+                        .map(|v| Some(#ident::#ident_variant(v)))
+                    },
+                    _ => quote! {
+                        #serde::Deserialize::deserialize(deserializer)
+                            .map(|v| Some(#ident::#ident_variant(v)))
+                    },
+                });
+            }
+            let ident_seed = format_ident!("FieldSeed");
+            let ident_value = format_ident!("value");
+            // the JSON names of the members are accepted as well as the proto names, unless they
+            // are renamed
+            let accepts_json_names = derive_meta.rename_all_variants.is_none();
+            let name_match = expand_name_match(
+                &ident_value,
+                names
+                    .iter()
+                    .flat_map(|name| {
+                        let json_name = to_json_name(name);
+                        let stmt = quote! { return Some(#name); };
+                        (accepts_json_names && json_name != *name)
+                            .then(|| (json_name, stmt.clone()))
+                            .into_iter()
+                            .chain([(name.clone(), stmt)])
+                    })
+                    .collect(),
+            );
+
+            // deserializes the value of an already matched oneof member straight from the map
+            // of its parent message, without buffering it
             return quote! {
+                struct #ident_seed(&'static str, _prost_serde_derive::__private::MemberOptions);
+
+                impl<'de> #serde::de::DeserializeSeed<'de> for #ident_seed {
+                    type Value = Option<#ident>;
+
+                    fn deserialize<D>(self, deserializer: D) -> Result<Option<#ident>, D::Error>
+                    where
+                        D: #serde::Deserializer<'de>,
+                    {
+                        let options = self.1;
+                        match self.0 {
+                            #(#names => #member_exprs,)*
+                            name => Err(#serde::de::Error::unknown_field(name, #ident::field_names())),
+                        }
+                    }
+                }
+
                 impl #ident {
                     pub fn field_names() -> &'static [&'static str] {
-                        &[#(#variants),*]
+                        &[#(#names),*]
+                    }
+
+                    #[doc(hidden)]
+                    pub fn match_field_name(#ident_value: &[u8]) -> Option<&'static str> {
+                        #name_match
+                        None
+                    }
+
+                    #[doc(hidden)]
+                    pub fn field_seed<'de>(
+                        name: &str,
+                        options: _prost_serde_derive::__private::MemberOptions,
+                    ) -> Option<impl #serde::de::DeserializeSeed<'de, Value = Option<Self>>> {
+                        Self::match_field_name(name.as_bytes()).map(|name| #ident_seed(name, options))
                     }
                 }
             };
@@ -1,14 +1,64 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Ident, Path};
+use syn::{Ident, Path, Type};
 
-use crate::attr::{DeriveMeta, FieldModifier, ProstAttr, ProtobufType};
+use crate::attr::{BytesEncoding, DeriveMeta, FieldMeta, FieldModifier, ProstAttr, ProtobufType};
 use crate::context::Context;
-use crate::util::into_syn_error;
+use crate::util::{get_enum_prefix, get_message_type, into_syn_error};
+
+/// Returns the seed of an enum value, which reads the names in the style of the enum. `lenient` and
+/// `ignore_unknown` are the expressions of the options of the message.
+pub fn get_enum_seed(
+    path: &Path,
+    lenient: TokenStream,
+    ignore_unknown: TokenStream,
+) -> TokenStream {
+    let prefix = path
+        .segments
+        .last()
+        .map(|segment| get_enum_prefix(&segment.ident))
+        .unwrap_or_default();
+    quote! {
+        _prost_serde_derive::__private::EnumSeed {
+            values: {
+                use _prost_serde_derive::__private::{ProtoValues as _, StyledValues as _};
+                (&_prost_serde_derive::__private::EnumStyle::<#path>::new())
+                    .values(|name| #path::from_str_name(name).map(|v| v as i32))
+            },
+            proto_values: |name| #path::from_str_name(name).map(|v| v as i32),
+            is_valid: |v| <#path as ::core::convert::TryFrom<i32>>::try_from(v).is_ok(),
+            prefix: #prefix,
+            lenient: #lenient,
+            ignore_unknown: #ignore_unknown,
+        }
+    }
+}
+
+/// Returns the seed of a `bytes` value in the encoding of the field, which gives a `Vec<u8>`.
+pub fn get_bytes_seed(encoding: BytesEncoding) -> TokenStream {
+    match encoding {
+        BytesEncoding::Base64 | BytesEncoding::Base64Url => quote! {
+            _prost_serde_derive::__private::BytesSeed {
+                decode: _prost_serde_derive::__private::decode_base64,
+                expecting: "a base64 string",
+            }
+        },
+        BytesEncoding::Hex => quote! {
+            _prost_serde_derive::__private::BytesSeed {
+                decode: _prost_serde_derive::__private::decode_hex,
+                expecting: "a hex string",
+            }
+        },
+        BytesEncoding::Array => quote! { _prost_serde_derive::__private::ByteArraySeed },
+    }
+}
 
 pub struct FieldVisitorTokenStream {
     pub value_getter_expr: TokenStream,
     pub narrowing_expr: TokenStream,
+    pub seq_element_expr: TokenStream,
+    pub in_place_setter_stmt: TokenStream,
+    pub in_place_reset_stmt: TokenStream,
 }
 
 #[allow(unused)]
@@ -27,125 +77,84 @@ impl<'a> FieldVisitorTokenGenerator<'a> {
         }
     }
 
-    pub fn get_value_getter_expr(&self, prost_attr: &ProstAttr) -> Result<TokenStream, ()> {
+    pub fn get_value_getter_expr(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        name: &TokenStream,
+    ) -> Result<TokenStream, ()> {
         match prost_attr.modifier {
-            FieldModifier::None => self.get_none_value_getter_expr(prost_attr),
-            FieldModifier::Repeated => self.get_repeated_value_getter_expr(prost_attr),
-            FieldModifier::Optional => self.get_optional_value_getter_expr(prost_attr),
+            FieldModifier::None => self.get_none_value_getter_expr(prost_attr, field_meta, name),
+            FieldModifier::Repeated => {
+                self.get_repeated_value_getter_expr(prost_attr, field_meta, name)
+            }
+            FieldModifier::Optional => {
+                self.get_optional_value_getter_expr(prost_attr, field_meta, name)
+            }
         }
     }
 
-    fn get_none_value_getter_expr(&self, prost_attr: &ProstAttr) -> Result<TokenStream, ()> {
-        let serde = self.serde;
-        let defaut_value = prost_attr.get_default_value();
+    fn get_none_value_getter_expr(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        name: &TokenStream,
+    ) -> Result<TokenStream, ()> {
+        let default_value = self.get_default_value(prost_attr, field_meta);
 
         match prost_attr.ty {
-            ProtobufType::Enumeration(ref path) => Ok(self.value_getter(
-                Some(quote! { String }),
-                quote! {
-                    Some(match #path::from_str_name(&value) {
-                        Some(v) => v.into(),
-                        None => return Err(#serde::de::Error::unknown_variant(&value, &[])),
-                    })
-                },
-                defaut_value,
+            // an ignored unknown enum value leaves the field at its default
+            ProtobufType::Enumeration(_) => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { Some(value.unwrap_or_else(|| #default_value)) },
+                default_value,
             )),
-            ProtobufType::Bytes(_) => Ok(self.value_getter(
-                Some(quote! { String }),
-                quote! {
-                    Some({
-                        extern crate base64 as _base64;
-                        match _base64::decode(&value) {
-                            Ok(v) => v.into(),
-                            Err(_) => return Err(#serde::de::Error::invalid_value(#serde::de::Unexpected::Str(&value), &"A base64 string")),
-                        }
-                    })
-                },
-                defaut_value,
+            ProtobufType::Bytes => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { Some(value.into()) },
+                default_value,
             )),
-            ProtobufType::OneOf(_) => {
-                let serde = self.serde;
-
-                Ok(self.value_getter(
-                    None,
-                    quote! {
-                        let mut collect = _serde::__private::Vec::<
-                            _serde::__private::Option<(
-                                _serde::__private::de::Content,
-                                _serde::__private::de::Content,
-                            )>,
-                        >::new();
-                        collect.push(_serde::__private::Some((#serde::__private::de::Content::String(name), value)));
-
-                        Some(
-                            #serde::de::Deserialize::deserialize(
-                                #serde::__private::de::FlatMapDeserializer(
-                                    &mut collect,
-                                    _serde::__private::PhantomData,
-                                )
-                            )?
-                        )
-                    },
-                    defaut_value,
-                ))
-            },
             _ => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
                 None,
                 quote! { Some(value) },
-                defaut_value,
+                default_value,
             )),
         }
     }
 
-    fn get_repeated_value_getter_expr(&self, prost_attr: &ProstAttr) -> Result<TokenStream, ()> {
-        let serde = self.serde;
-        let default_value = prost_attr.get_default_value();
+    fn get_repeated_value_getter_expr(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        name: &TokenStream,
+    ) -> Result<TokenStream, ()> {
+        let default_value = self.get_default_value(prost_attr, field_meta);
 
         match prost_attr.ty {
-            ProtobufType::Enumeration(ref path) => Ok(self.value_getter(
-                Some(quote! { Vec<String> }),
-                quote! {
-                    Some({
-                        let mut result = vec![];
-                        for value in value.iter() {
-                            match #path::from_str_name(&value) {
-                                Some(v) => {
-                                    result.push(v.into());
-                                }
-                                None => {
-                                    return Err(#serde::de::Error::unknown_variant(&value, &[]));
-                                }
-                            }
-                        }
-                        result
-                    })
-                },
+            // ignored unknown enum values are dropped from the list
+            ProtobufType::Enumeration(_) => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { Some(value.into_iter().flatten().collect()) },
                 default_value,
             )),
-            ProtobufType::Bytes(_) => Ok(self.value_getter(
-                Some(quote! { Vec<String> }),
-                quote! {
-                    Some({
-                        extern crate base64 as _base64;
-                        let mut result = vec![];
-                        for value in value.iter() {
-                            match _base64::decode(value) {
-                                Ok(v) => {
-                                    result.push(v.into());
-                                },
-                                Err(_) => {
-                                    return Err(
-                                        #serde::de::Error::invalid_value(
-                                            #serde::de::Unexpected::Str(value),
-                                            &"a base64 string",
-                                        ),
-                                    );
-                                }
-                            }
-                        }
-                        result
-                    })
-                },
+            ProtobufType::Bytes => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { Some(value.into_iter().map(Into::into).collect()) },
                 default_value,
             )),
             ProtobufType::OneOf(ref path) => {
@@ -156,6 +165,9 @@ impl<'a> FieldVisitorTokenGenerator<'a> {
                 Err(())
             }
             _ => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
                 Some(quote! { Vec<_> }),
                 quote! { Some(value) },
                 default_value,
@@ -163,39 +175,29 @@ impl<'a> FieldVisitorTokenGenerator<'a> {
         }
     }
 
-    fn get_optional_value_getter_expr(&self, prost_attr: &ProstAttr) -> Result<TokenStream, ()> {
-        let serde = self.serde;
-        let default_value = prost_attr.get_default_value();
+    fn get_optional_value_getter_expr(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        name: &TokenStream,
+    ) -> Result<TokenStream, ()> {
+        let default_value = self.get_default_value(prost_attr, field_meta);
 
         match prost_attr.ty {
-            ProtobufType::Enumeration(ref path) => Ok(self.value_getter(
-                Some(quote! { Option<String> }),
-                quote! {
-                    match &value {
-                        Some(v) => match #path::from_str_name(v) {
-                            Some(v) => Some(v.into()),
-                            None => return Err(#serde::de::Error::unknown_variant(v, &[])),
-                        },
-                        None => None,
-                    }
-                },
+            ProtobufType::Enumeration(_) => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { value.flatten() },
                 default_value,
             )),
-            ProtobufType::Bytes(_) => Ok(self.value_getter(
-                Some(quote! { Option<String> }),
-                quote! {
-                    if let Some(value) = value.as_ref() {
-                        Some({
-                            extern crate base64 as _base64;
-                            match _base64::decode(&value) {
-                                Ok(v) => v.into(),
-                                Err(_) => return Err(#serde::de::Error::invalid_value(#serde::de::Unexpected::Str(&value), &"a base64 string")),
-                            }
-                        })
-                    } else {
-                        None
-                    }
-                },
+            ProtobufType::Bytes => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { value.map(Into::into) },
                 default_value,
             )),
             ProtobufType::OneOf(ref path) => {
@@ -205,34 +207,301 @@ impl<'a> FieldVisitorTokenGenerator<'a> {
                 ));
                 Err(())
             }
-            _ => Ok(self.value_getter(None, quote! { value }, default_value)),
+            _ => Ok(self.value_getter(
+                prost_attr,
+                field_meta,
+                name,
+                None,
+                quote! { value },
+                default_value,
+            )),
+        }
+    }
+
+    /// Reads the field at `index` of a positional encoding.
+    fn get_seq_element_expr(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        ty: &Type,
+        index: usize,
+    ) -> TokenStream {
+        let serde = self.serde;
+
+        let (element, expr) = match (self.get_seed(prost_attr, field_meta), &prost_attr.modifier) {
+            // enum values are numbers here, which are never ignored
+            (Some(seed), modifier) if matches!(prost_attr.ty, ProtobufType::Enumeration(_)) => {
+                let expr = match modifier {
+                    FieldModifier::None => quote! { value.unwrap_or_default() },
+                    FieldModifier::Optional => quote! { value.flatten() },
+                    FieldModifier::Repeated => quote! { value.into_iter().flatten().collect() },
+                };
+                (quote! { next_element_seed(#seed) }, expr)
+            }
+            // bytes are read as `Vec<u8>`, which may be stored as `Bytes`
+            (Some(seed), modifier) if matches!(prost_attr.ty, ProtobufType::Bytes) => {
+                let expr = match modifier {
+                    FieldModifier::None => quote! { value.into() },
+                    FieldModifier::Optional => quote! { value.map(Into::into) },
+                    FieldModifier::Repeated => {
+                        quote! { value.into_iter().map(Into::into).collect() }
+                    }
+                };
+                (quote! { next_element_seed(#seed) }, expr)
+            }
+            (Some(seed), _) => (quote! { next_element_seed(#seed) }, quote! { value }),
+            (None, _) => (quote! { next_element::<#ty>() }, quote! { value }),
+        };
+
+        quote! {
+            match seq.#element? {
+                Some(value) => #expr,
+                None => return Err(#serde::de::Error::invalid_length(#index, &self)),
+            }
+        }
+    }
+
+    /// Assigns the field of `self.place`, reusing its allocations where the value is not converted.
+    fn get_in_place_setter_stmt(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        ty: &Type,
+        name: &TokenStream,
+        ident_field: &Ident,
+        value_getter_expr: &TokenStream,
+    ) -> TokenStream {
+        match (&prost_attr.ty, &prost_attr.modifier) {
+            (
+                ProtobufType::Enumeration(_) | ProtobufType::Bytes | ProtobufType::OneOf(_),
+                FieldModifier::Optional,
+            ) => {
+                return quote! {
+                    self.place.#ident_field = #value_getter_expr;
+                };
+            }
+            (ProtobufType::Enumeration(_) | ProtobufType::Bytes | ProtobufType::OneOf(_), _) => {
+                return quote! {
+                    if let Some(value) = #value_getter_expr {
+                        self.place.#ident_field = value;
+                    }
+                };
+            }
+            // numbers and booleans are read by their seeds, and have no allocations to reuse but
+            // repeated ones
+            (ty, FieldModifier::Optional) if ty.is_number() || matches!(ty, ProtobufType::Bool) => {
+                return quote! {
+                    self.place.#ident_field = #value_getter_expr;
+                };
+            }
+            (ty, _) if ty.is_number() || matches!(ty, ProtobufType::Bool) => {
+                return quote! {
+                    if let Some(value) = #value_getter_expr {
+                        self.place.#ident_field = value;
+                    }
+                };
+            }
+            // the strings are only reused when they are not comma-separated
+            (ProtobufType::String, FieldModifier::Repeated) => {
+                let string_input = self.get_string_input_expr();
+                let in_place_stmt =
+                    self.get_in_place_seed_stmt(prost_attr, field_meta, ty, name, ident_field);
+                return quote! {
+                    if #string_input {
+                        if let Some(value) = #value_getter_expr {
+                            self.place.#ident_field = value;
+                        }
+                    } else {
+                        #in_place_stmt
+                    }
+                };
+            }
+            _ => {}
+        }
+
+        self.get_in_place_seed_stmt(prost_attr, field_meta, ty, name, ident_field)
+    }
+
+    /// Deserializes the field into `self.place` by `Deserialize::deserialize_in_place`.
+    fn get_in_place_seed_stmt(
+        &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        ty: &Type,
+        name: &TokenStream,
+        ident_field: &Ident,
+    ) -> TokenStream {
+        let seed = match (&prost_attr.ty, &prost_attr.modifier) {
+            (ProtobufType::Message, FieldModifier::None) | (_, FieldModifier::Optional) => {
+                let inner_ty = get_message_type(ty);
+                quote! {
+                    _prost_serde_derive::__private::OptionInPlaceSeed::<_, #inner_ty>::new(
+                        &mut self.place.#ident_field,
+                    )
+                }
+            }
+            _ => quote! {
+                _prost_serde_derive::__private::InPlaceSeed(&mut self.place.#ident_field)
+            },
+        };
+        let getter = match prost_attr.ty {
+            ProtobufType::Message => quote! {
+                {
+                    let _scope = _prost_serde_derive::__private::FieldScope::enter(#name);
+                    map.next_value_seed(#seed)
+                }
+            },
+            _ => quote! { map.next_value_seed(#seed) },
+        };
+
+        let default_value = self.get_default_value(prost_attr, field_meta);
+        let default_value = match prost_attr.modifier {
+            FieldModifier::Optional => quote! { Some(#default_value) },
+            _ => default_value,
+        };
+        let omit_type_errors = self.get_omit_type_errors_expr(field_meta);
+        quote! {
+            match #getter {
+                Ok(()) => {}
+                Err(err) if #omit_type_errors => {
+                    _prost_serde_derive::__private::report_omitted(#name, &err);
+                    self.place.#ident_field = #default_value;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the value used in place of a missing or omitted value.
+    fn get_default_value(&self, prost_attr: &ProstAttr, field_meta: &FieldMeta) -> TokenStream {
+        match &field_meta.default {
+            Some(path) => quote! { #path() },
+            None => prost_attr.get_default_value(),
+        }
+    }
+
+    /// Returns whether type errors are omitted. `omit_type_errors` is set by the visitor from the
+    /// container attribute and the parse options, which also override the field attribute.
+    fn get_omit_type_errors_expr(&self, field_meta: &FieldMeta) -> TokenStream {
+        if field_meta.omit_type_errors {
+            quote! { _prost_serde_derive::__private::omit_type_errors(true) }
+        } else {
+            quote! { omit_type_errors }
         }
     }
 
+    /// Returns the seed of an enumeration, `bytes` or number field. Enum values and encoded bytes
+    /// are read from borrowed strings, numbers from the representations of the protobuf JSON
+    /// mapping, and the seeds handle non-human-readable formats by themselves.
+    fn get_seed(&self, prost_attr: &ProstAttr, field_meta: &FieldMeta) -> Option<TokenStream> {
+        let seed = match prost_attr.ty {
+            ProtobufType::Enumeration(ref path) => {
+                let lenient_enums = self.meta.lenient_enums;
+                let ignore_unknown_fields = self.meta.ignore_unknown_fields;
+                get_enum_seed(
+                    path,
+                    quote! { _prost_serde_derive::__private::lenient_enums(#lenient_enums) },
+                    quote! {
+                        _prost_serde_derive::__private::ignore_unknown_fields(#ignore_unknown_fields)
+                    },
+                )
+            }
+            ProtobufType::Bytes => get_bytes_seed(field_meta.bytes_encoding),
+            ref ty if ty.is_number() => quote! {
+                _prost_serde_derive::__private::NumberSeed::new()
+            },
+            ProtobufType::Bool => {
+                let string_input = self.get_string_input_expr();
+                quote! {
+                    _prost_serde_derive::__private::BoolSeed { strings: #string_input }
+                }
+            }
+            // repeated strings may be comma-separated
+            ProtobufType::String if matches!(prost_attr.modifier, FieldModifier::Repeated) => {
+                quote! { ::core::marker::PhantomData }
+            }
+            _ => return None,
+        };
+        Some(match prost_attr.modifier {
+            FieldModifier::None => seed,
+            FieldModifier::Optional => quote! { _prost_serde_derive::__private::OptionSeed(#seed) },
+            FieldModifier::Repeated => {
+                let string_input = self.get_string_input_expr();
+                quote! {
+                    _prost_serde_derive::__private::RepeatedSeed {
+                        seed: #seed,
+                        split_strings: #string_input,
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns whether every value may be given as a string, by the container attribute or the
+    /// parse options.
+    pub fn get_string_input_expr(&self) -> TokenStream {
+        let string_input = self.meta.string_input;
+        quote! { _prost_serde_derive::__private::string_input(#string_input) }
+    }
+
     fn value_getter(
         &self,
+        prost_attr: &ProstAttr,
+        field_meta: &FieldMeta,
+        name: &TokenStream,
         type_sig: Option<TokenStream>,
         expr: TokenStream,
         default_value: TokenStream,
     ) -> TokenStream {
-        let getter = match type_sig {
-            Some(v) => quote! { map.next_value::<#v>() },
-            None => quote! { map.next_value() },
-        };
-
-        if self.meta.omit_type_errors {
-            quote! {
-                match #getter {
-                    Ok(value) => #expr,
-                    Err(_) => Some(#default_value)
+        let getter = match (&prost_attr.ty, type_sig) {
+            // the whole oneof is keyed by the field name in non-human-readable formats
+            (ProtobufType::OneOf(path), _) => {
+                let lenient_enums = self.meta.lenient_enums;
+                let ignore_unknown_fields = self.meta.ignore_unknown_fields;
+                quote! {
+                    map.next_value_seed(_prost_serde_derive::__private::OneofSeed(
+                        #path::field_seed(
+                            #name,
+                            _prost_serde_derive::__private::MemberOptions {
+                                lenient_enums: _prost_serde_derive::__private::lenient_enums(
+                                    #lenient_enums,
+                                ),
+                                ignore_unknown_fields:
+                                    _prost_serde_derive::__private::ignore_unknown_fields(
+                                        #ignore_unknown_fields,
+                                    ),
+                            },
+                        ),
+                    ))
                 }
             }
-        } else {
-            quote! {
+            (_, type_sig) => match (self.get_seed(prost_attr, field_meta), type_sig) {
+                (Some(seed), _) => quote! { map.next_value_seed(#seed) },
+                (None, Some(v)) => quote! { map.next_value::<#v>() },
+                (None, None) => quote! { map.next_value() },
+            },
+        };
+
+        // errors omitted inside nested messages are reported under this field's path
+        let getter = match prost_attr.ty {
+            ProtobufType::Message | ProtobufType::OneOf(_) => quote! {
                 {
-                    let value = #getter?;
-                    #expr
+                    let _scope = _prost_serde_derive::__private::FieldScope::enter(#name);
+                    #getter
+                }
+            },
+            _ => getter,
+        };
+
+        let omit_type_errors = self.get_omit_type_errors_expr(field_meta);
+        quote! {
+            match #getter {
+                Ok(value) => #expr,
+                Err(err) if #omit_type_errors => {
+                    _prost_serde_derive::__private::report_omitted(#name, &err);
+                    Some(#default_value)
                 }
+                Err(err) => return Err(err),
             }
         }
     }
@@ -240,26 +509,50 @@ impl<'a> FieldVisitorTokenGenerator<'a> {
     pub fn expand(
         &self,
         prost_attr: &ProstAttr,
-        field_name: &String,
+        field_meta: &FieldMeta,
+        ty: &Type,
+        index: usize,
+        ident_field: &Ident,
         ident_field_var: &Ident,
     ) -> Result<FieldVisitorTokenStream, ()> {
         let serde = self.serde;
-        let default_value = prost_attr.get_default_value();
+        let field_name = field_meta.get_name(ident_field);
+        let default_value = self.get_default_value(prost_attr, field_meta);
 
-        let value_getter_expr = self.get_value_getter_expr(prost_attr)?;
+        let name = match prost_attr.ty {
+            ProtobufType::OneOf(_) => quote! { name },
+            _ => quote! { #field_name },
+        };
+        let value_getter_expr = self.get_value_getter_expr(prost_attr, field_meta, &name)?;
+        let seq_element_expr = self.get_seq_element_expr(prost_attr, field_meta, ty, index);
+        let in_place_setter_stmt = self.get_in_place_setter_stmt(
+            prost_attr,
+            field_meta,
+            ty,
+            &name,
+            ident_field,
+            &value_getter_expr,
+        );
 
+        // a field with a default function takes it whenever the field is missing, and so does a
+        // field whose default value is skipped on output, so that the output round-trips
+        let default_if_missing = field_meta.default.is_some()
+            || (field_meta.skip_serializing_if_default
+                && matches!(prost_attr.modifier, FieldModifier::None));
         let narrowing_expr = match prost_attr.modifier {
-            FieldModifier::None => {
-                if self.meta.use_default_for_missing_fields {
-                    quote! {
-                        let #ident_field_var = #ident_field_var.unwrap_or(#default_value);
-                    }
-                } else {
-                    quote! {
-                        let #ident_field_var = #ident_field_var.ok_or_else(|| #serde::de::Error::missing_field(#field_name))?;
-                    }
-                }
-            }
+            _ if default_if_missing => quote! {
+                let #ident_field_var = match #ident_field_var {
+                    Some(value) => value,
+                    None => #default_value,
+                };
+            },
+            FieldModifier::None => quote! {
+                let #ident_field_var = match #ident_field_var {
+                    Some(value) => value,
+                    None if use_default_for_missing_fields => #default_value,
+                    None => return Err(#serde::de::Error::missing_field(#field_name)),
+                };
+            },
             FieldModifier::Repeated => {
                 quote! {
                     let #ident_field_var = #ident_field_var.unwrap_or(vec![]);
@@ -268,9 +561,31 @@ impl<'a> FieldVisitorTokenGenerator<'a> {
             _ => quote! {},
         };
 
+        let in_place_reset_stmt = match prost_attr.modifier {
+            _ if default_if_missing => quote! {
+                self.place.#ident_field = #default_value;
+            },
+            FieldModifier::None => quote! {
+                if use_default_for_missing_fields {
+                    self.place.#ident_field = #default_value;
+                } else {
+                    return Err(#serde::de::Error::missing_field(#field_name));
+                }
+            },
+            FieldModifier::Repeated => quote! {
+                self.place.#ident_field.clear();
+            },
+            FieldModifier::Optional => quote! {
+                self.place.#ident_field = None;
+            },
+        };
+
         Ok(FieldVisitorTokenStream {
             value_getter_expr,
             narrowing_expr,
+            seq_element_expr,
+            in_place_setter_stmt,
+            in_place_reset_stmt,
         })
     }
 }
@@ -8,9 +8,11 @@ use syn::ext::IdentExt;
 use syn::{parse_quote, DataStruct, Fields, FieldsNamed, Path, Type};
 
 use super::field::FieldVisitorTokenStream;
-use crate::attr::{DeriveMeta, ProstAttr, ProtobufType};
+use super::DeserializeTokenStream;
+use crate::attr::{DeriveMeta, FieldMeta, FieldModifier, ProstAttr, ProtobufType};
 use crate::context::Context;
 use crate::deserialize::field::FieldVisitorTokenGenerator;
+use crate::util::expand_name_match;
 
 static IDENT_VARIANT_UNKNOWN: &str = "_Unknown";
 
@@ -21,7 +23,7 @@ pub fn expand_struct(
     deserializer: &Ident,
     ident: &Ident,
     data: &DataStruct,
-) -> Result<TokenStream, ()> {
+) -> Result<DeserializeTokenStream, ()> {
     match &data.fields {
         Fields::Named(f) => {
             NamedStructDeserializer::new(context, meta, serde, deserializer, ident, f)?.expand()
@@ -97,7 +99,9 @@ impl SingleFieldVariant {
 
 struct Field {
     ident: Ident,
+    ty: Type,
     attr: ProstAttr,
+    meta: FieldMeta,
 }
 
 struct NamedStructDeserializer<'a> {
@@ -118,14 +122,22 @@ impl<'a> NamedStructDeserializer<'a> {
         ident: &'a Ident,
         fields: &'a FieldsNamed,
     ) -> Result<Self, ()> {
-        let mut typed_fields = Vec::new();
-
-        for field in fields.named.iter() {
-            let prost_attr = ProstAttr::from_ast(context, &field.attrs)?;
-            typed_fields.push(Field {
-                ident: field.ident.as_ref().unwrap().clone(),
-                attr: prost_attr,
+        // parse every field before bailing out so that all of the errors are reported at once
+        let (typed_fields, errors): (Vec<_>, Vec<()>) = fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                ProstAttr::from_ast(context, ident, &field.attrs).map(|attr| Field {
+                    ident: ident.clone(),
+                    ty: field.ty.clone(),
+                    meta: FieldMeta::from_ast(context, meta, ident, &attr, &field.attrs),
+                    attr,
+                })
             })
+            .partition_result();
+        if !errors.is_empty() {
+            return Err(());
         }
 
         Ok(Self {
@@ -139,13 +151,29 @@ impl<'a> NamedStructDeserializer<'a> {
     }
 
     #[inline]
-    fn get_field_idents(&self) -> impl Iterator<Item = &Ident> {
-        self.fields.iter().map(|v| &v.ident)
+    fn get_field_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.fields.iter().map(|v| v.meta.get_name(&v.ident))
+    }
+
+    /// Returns the where clause bounding the message fields, for every impl reading them.
+    fn get_where_clause(&self) -> TokenStream {
+        let ident_assert = format_ident!("DeserializeMessage");
+        let lifetime = parse_quote! { 'de };
+        let bounds = self
+            .fields
+            .iter()
+            .filter_map(|v| v.attr.get_field_bound(&v.ty, &ident_assert, &lifetime))
+            .collect::<Vec<_>>();
+        if bounds.is_empty() {
+            quote! {}
+        } else {
+            quote! { where #(#bounds),* }
+        }
     }
 
     fn expand_field_deserializer_impl(
         &self,
-        ident_unknown: Option<&Ident>,
+        ident_unknown: &Ident,
     ) -> (Ident, TokenStream, Vec<SingleFieldVariant>) {
         let serde = self.serde;
 
@@ -154,68 +182,65 @@ impl<'a> NamedStructDeserializer<'a> {
             let ident = format_ident!("{}", field.ident.unraw().to_string().to_case(Case::Pascal));
             if let ProtobufType::OneOf(_) = field.attr.ty {
                 // keep oneof field name inside
-                let ty_string: Type = parse_quote!(String);
-                variants.push(SingleFieldVariant::new(ident, Some(ty_string)));
+                let ty_name: Type = parse_quote!(&'static str);
+                variants.push(SingleFieldVariant::new(ident, Some(ty_name)));
             } else {
                 variants.push(SingleFieldVariant::new(ident, None));
             }
         }
         // TODO: show oneof fields
         let field_names = self
-            .get_field_idents()
+            .get_field_names()
             .map(|v| format!("`{}`", v))
             .join(" or ");
 
         let ident_enum = format_ident!("Field");
         let ident_visitor = format_ident!("{}Visitor", ident_enum);
 
+        let ident_value = format_ident!("value");
         let mut oneof_field_if_exprs = Vec::new();
         let mut field_match_arms = Vec::new();
 
         for (field, variant) in iter::zip(self.fields.iter(), variants.iter()) {
+            let name = field.meta.get_name(&field.ident);
             if let ProtobufType::OneOf(ref p) = field.attr.ty {
-                let names = quote! { #p::field_names() };
-                let variant_gen = variant.gen(Some(quote! { value.to_string() }));
+                let variant_gen = variant.gen(Some(quote! { name }));
                 oneof_field_if_exprs.push(quote! {
-                    if #names.contains(&value) {
+                    if let Some(name) = #p::match_field_name(#ident_value) {
                         return Ok(#ident_enum::#variant_gen);
                     }
-                })
+                });
+                // non-human-readable formats key the whole oneof by the field name
+                let variant_gen = variant.gen(Some(quote! { #name }));
+                field_match_arms.push((name, quote! { return Ok(#ident_enum::#variant_gen); }));
             } else {
-                let name = field.ident.unraw().to_string();
                 let variant = variant.ident();
-                field_match_arms.push(quote! {
-                    #name => return Ok(#ident_enum::#variant)
-                });
+                let stmt = quote! { return Ok(#ident_enum::#variant); };
+                for alternative_name in field.meta.get_alternative_names(&field.ident) {
+                    field_match_arms.push((alternative_name, stmt.clone()));
+                }
+                field_match_arms.push((name, stmt));
             }
         }
+        let field_match = expand_name_match(&ident_value, field_match_arms);
 
-        let oneof_field_if_exprs = if oneof_field_if_exprs.len() > 1 {
-            oneof_field_if_exprs
-                .into_iter()
-                .reduce(|acc, i| quote! { #acc else #i})
-                .unwrap()
-        } else {
-            quote! { #(#oneof_field_if_exprs)* }
-        };
-
-        let (unknown_variant, unknown_match) = if let Some(unknown) = ident_unknown {
-            (
-                Some(quote! { #unknown, }),
-                quote! { Ok(#ident_enum::#unknown) },
-            )
-        } else {
-            (
-                None,
-                quote! { Err(#serde::de::Error::unknown_field(value, FIELDS)) },
-            )
+        let ignore_unknown_fields = self.meta.ignore_unknown_fields;
+        let unknown_match = quote! {
+            if _prost_serde_derive::__private::ignore_unknown_fields(#ignore_unknown_fields) {
+                Ok(#ident_enum::#ident_unknown)
+            } else {
+                Err(#serde::de::Error::unknown_field(
+                    &::std::string::String::from_utf8_lossy(#ident_value),
+                    FIELDS,
+                ))
+            }
         };
 
         let variant_defs = variants.iter().map(SingleFieldVariant::def);
 
         let expr = quote! {
             enum #ident_enum {
-                #unknown_variant
+                #ident_unknown,
                 #(#variant_defs),*
             }
 
@@ -237,12 +262,23 @@ impl<'a> NamedStructDeserializer<'a> {
                         where
                             E: #serde::de::Error,
                         {
-                            match value {
-                                #(#field_match_arms,)*
-                                _ => {}
-                            }
+                            self.visit_bytes(value.as_bytes())
+                        }
+
+                        fn visit_borrowed_str<E>(self, value: &'de str) -> Result<#ident_enum, E>
+                        where
+                            E: #serde::de::Error,
+                        {
+                            self.visit_bytes(value.as_bytes())
+                        }
 
-                            #oneof_field_if_exprs
+                        fn visit_bytes<E>(self, #ident_value: &[u8]) -> Result<#ident_enum, E>
+                        where
+                            E: #serde::de::Error,
+                        {
+                            #field_match
+
+                            #(#oneof_field_if_exprs)*
 
                             #unknown_match
                         }
@@ -256,22 +292,18 @@ impl<'a> NamedStructDeserializer<'a> {
         (ident_enum, expr, variants)
     }
 
-    fn expand_visitor_impl(&self) -> Result<(Ident, TokenStream), ()> {
+    fn expand_visitor_impl(&self) -> Result<(Ident, Ident, TokenStream), ()> {
         let serde = self.serde;
         let unknown = format_ident!("{}", IDENT_VARIANT_UNKNOWN);
 
         let ident_self = self.ident;
         let expecting = format!("struct {}", ident_self);
         let ident_visitor = format_ident!("Visitor");
-
-        let ident_unknown = if self.meta.ignore_unknown_fields {
-            Some(&unknown)
-        } else {
-            None
-        };
+        let where_clause = self.get_where_clause();
+        let ident_in_place_visitor = format_ident!("InPlaceVisitor");
 
         let (ident_field_enum, field_deserializer, field_variants) =
-            self.expand_field_deserializer_impl(ident_unknown);
+            self.expand_field_deserializer_impl(&unknown);
 
         let field_visitor_token_generator =
             FieldVisitorTokenGenerator::new(self.context, self.meta, self.serde);
@@ -280,41 +312,139 @@ impl<'a> NamedStructDeserializer<'a> {
         let mut var_match_arms = vec![];
         let mut var_narrowings = vec![];
         let mut var_fields = vec![];
+        let mut var_seq_elements = vec![];
+        let mut in_place_decls = vec![];
+        let mut in_place_match_arms = vec![];
+        let mut in_place_resets = vec![];
+        let mut has_error = false;
+
+        // unknown keys are only let through when they are ignored
+        let unknown_match_arm = quote! {
+            #ident_field_enum::#unknown => {
+                map.next_value::<#serde::de::IgnoredAny>()?;
+            }
+        };
+        var_match_arms.push(unknown_match_arm.clone());
+        in_place_match_arms.push(unknown_match_arm);
+
+        let omit_type_errors = self.meta.omit_type_errors;
+        let use_default_for_missing_fields = self.meta.use_default_for_missing_fields;
+        let option_decls = quote! {
+            let omit_type_errors =
+                _prost_serde_derive::__private::omit_type_errors(#omit_type_errors);
+            let use_default_for_missing_fields =
+                _prost_serde_derive::__private::use_default_for_missing_fields(
+                    #use_default_for_missing_fields,
+                );
+        };
 
-        if self.meta.ignore_unknown_fields {
-            var_match_arms.push(quote! {
-                #ident_field_enum::#unknown => {
-                    map.next_value::<serde_json::Value>()?;
-                }
-            });
-        }
-
-        for (field, field_variant) in iter::zip(self.fields.iter(), field_variants.iter()) {
+        for (index, (field, field_variant)) in
+            iter::zip(self.fields.iter(), field_variants.iter()).enumerate()
+        {
             let ident_field_var = format_ident!("psd_{}", field.ident.unraw());
+            let ident_field_seen = format_ident!("psd_{}_seen", field.ident.unraw());
             let ident_field = &field.ident;
-            let field_name = ident_field.to_string();
-            var_decls.push(quote! { let mut #ident_field_var = None; });
+            let field_name = field.meta.get_name(ident_field);
+            let ty = &field.ty;
+            var_decls.push(match field.attr.modifier {
+                // appended to by the values of repeated keys, so the type has to be known upfront
+                FieldModifier::Repeated => quote! { let mut #ident_field_var: Option<#ty> = None; },
+                _ => quote! { let mut #ident_field_var = None; },
+            });
+            in_place_decls.push(quote! { let mut #ident_field_seen = false; });
 
-            let FieldVisitorTokenStream {
+            let Ok(FieldVisitorTokenStream {
                 value_getter_expr,
                 narrowing_expr,
-            } = field_visitor_token_generator.expand(&field.attr, &field_name, &ident_field_var)?;
+                seq_element_expr,
+                in_place_setter_stmt,
+                in_place_reset_stmt,
+            }) = field_visitor_token_generator.expand(
+                &field.attr,
+                &field.meta,
+                &field.ty,
+                index,
+                ident_field,
+                &ident_field_var,
+            )
+            else {
+                has_error = true;
+                continue;
+            };
 
             let field_variant_pat = field_variant.pat();
-            var_match_arms.push(quote! {
-                #ident_field_enum::#field_variant_pat => {
-                    if #ident_field_var.is_some() {
-                        return Err(#serde::de::Error::duplicate_field(#field_name));
+            if let FieldModifier::Repeated = field.attr.modifier {
+                // with string input, the values of a key given more than once are appended, like
+                // `?tag=a&tag=b` of query strings
+                let string_input = field_visitor_token_generator.get_string_input_expr();
+                var_match_arms.push(quote! {
+                    #ident_field_enum::#field_variant_pat => {
+                        if #ident_field_var.is_some() && !#string_input {
+                            return Err(#serde::de::Error::duplicate_field(#field_name));
+                        }
+
+                        let value: Option<#ty> = #value_getter_expr;
+                        match (&mut #ident_field_var, value) {
+                            (Some(values), Some(value)) => values.extend(value),
+                            (values, value) => *values = value,
+                        }
+                    }
+                });
+                in_place_match_arms.push(quote! {
+                    #ident_field_enum::#field_variant_pat => {
+                        if #ident_field_seen {
+                            if !#string_input {
+                                return Err(#serde::de::Error::duplicate_field(#field_name));
+                            }
+                            let value: Option<#ty> = #value_getter_expr;
+                            if let Some(value) = value {
+                                self.place.#ident_field.extend(value);
+                            }
+                        } else {
+                            #ident_field_seen = true;
+
+                            #in_place_setter_stmt
+                        }
                     }
+                });
+            } else {
+                var_match_arms.push(quote! {
+                    #ident_field_enum::#field_variant_pat => {
+                        if #ident_field_var.is_some() {
+                            return Err(#serde::de::Error::duplicate_field(#field_name));
+                        }
 
-                    #ident_field_var = #value_getter_expr;
+                        #ident_field_var = #value_getter_expr;
+                    }
+                });
+                in_place_match_arms.push(quote! {
+                    #ident_field_enum::#field_variant_pat => {
+                        if #ident_field_seen {
+                            return Err(#serde::de::Error::duplicate_field(#field_name));
+                        }
+                        #ident_field_seen = true;
+
+                        #in_place_setter_stmt
+                    }
+                });
+            }
+            var_narrowings.push(narrowing_expr);
+            in_place_resets.push(quote! {
+                if !#ident_field_seen {
+                    #in_place_reset_stmt
                 }
             });
-            var_narrowings.push(narrowing_expr);
 
             var_fields.push(quote! {
                 #ident_field: #ident_field_var
             });
+            var_seq_elements.push(quote! {
+                let #ident_field_var = #seq_element_expr;
+            });
+        }
+
+        if has_error {
+            return Err(());
         }
 
         let expr = quote! {
@@ -322,17 +452,29 @@ impl<'a> NamedStructDeserializer<'a> {
 
             struct #ident_visitor;
 
-            impl<'de> #serde::de::Visitor<'de> for #ident_visitor {
+            impl<'de> #serde::de::Visitor<'de> for #ident_visitor #where_clause {
                 type Value = #ident_self;
 
                 fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
                     formatter.write_str(#expecting)
                 }
 
+                fn visit_seq<V>(self, mut seq: V) -> Result<#ident_self, V::Error>
+                where
+                    V: #serde::de::SeqAccess<'de>,
+                {
+                    #(#var_seq_elements)*
+
+                    Ok(#ident_self {
+                        #(#var_fields),*
+                    })
+                }
+
                 fn visit_map<V>(self, mut map: V) -> Result<#ident_self, V::Error>
                 where
                     V: #serde::de::MapAccess<'de>,
                 {
+                    #option_decls
                     #(#var_decls)*
                     while let Some(key) = map.next_key::<#ident_field_enum>()? {
                         match key {
@@ -346,27 +488,69 @@ impl<'a> NamedStructDeserializer<'a> {
                     })
                 }
             }
+
+            struct #ident_in_place_visitor<'a> {
+                place: &'a mut #ident_self,
+            }
+
+            impl<'a, 'de> #serde::de::Visitor<'de> for #ident_in_place_visitor<'a> #where_clause {
+                type Value = ();
+
+                fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    formatter.write_str(#expecting)
+                }
+
+                fn visit_seq<V>(self, seq: V) -> Result<(), V::Error>
+                where
+                    V: #serde::de::SeqAccess<'de>,
+                {
+                    *self.place = #serde::de::Visitor::visit_seq(#ident_visitor, seq)?;
+                    Ok(())
+                }
+
+                fn visit_map<V>(self, mut map: V) -> Result<(), V::Error>
+                where
+                    V: #serde::de::MapAccess<'de>,
+                {
+                    #option_decls
+                    #(#in_place_decls)*
+                    while let Some(key) = map.next_key::<#ident_field_enum>()? {
+                        match key {
+                            #(#in_place_match_arms),*
+                        };
+                    }
+                    #(#in_place_resets)*
+
+                    Ok(())
+                }
+            }
         };
 
-        Ok((ident_visitor, expr))
+        Ok((ident_visitor, ident_in_place_visitor, expr))
     }
 
-    pub fn expand(&self) -> Result<TokenStream, ()> {
+    pub fn expand(&self) -> Result<DeserializeTokenStream, ()> {
         let deserializer = self.deserializer;
 
         let name = self.ident.to_string();
-        let fields = self
-            .get_field_idents()
-            .map(ToString::to_string)
-            .collect::<Vec<_>>();
-
-        let (visitor_ident, visitor_impl) = self.expand_visitor_impl()?;
-
-        Ok(quote! {
-            #visitor_impl
-
-            const FIELDS: &'static [&'static str] = &[ #(#fields), * ];
-            #deserializer.deserialize_struct(#name, &FIELDS, #visitor_ident)
+        let fields = self.get_field_names().collect::<Vec<_>>();
+
+        let (visitor_ident, in_place_visitor_ident, visitor_impl) = self.expand_visitor_impl()?;
+
+        // the visitors are shared by `deserialize` and `deserialize_in_place`
+        Ok(DeserializeTokenStream {
+            items: quote! {
+                #visitor_impl
+
+                const FIELDS: &'static [&'static str] = &[ #(#fields), * ];
+            },
+            deserialize_block: quote! {
+                #deserializer.deserialize_struct(#name, &FIELDS, #visitor_ident)
+            },
+            deserialize_in_place_block: Some(quote! {
+                #deserializer.deserialize_struct(#name, &FIELDS, #in_place_visitor_ident { place })
+            }),
+            where_clause: self.get_where_clause(),
         })
     }
 }
@@ -3,10 +3,10 @@ mod field;
 mod r#struct;
 
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{parse_quote, Data, DeriveInput, Error, Path};
 
-use self::r#enum::{expand_enum, expand_oneof_field_name_method};
+use self::r#enum::{expand_enum, expand_enum_style_impl, expand_oneof_methods};
 use self::r#struct::expand_struct;
 use crate::attr::{DeriveMeta, ProstType};
 use crate::context::Context;
@@ -15,7 +15,7 @@ use crate::util::wrap_block;
 pub fn expand_serialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>> {
     let context = Context::new();
 
-    let Ok(derive_meta) = DeriveMeta::from_ast(&context, &input.attrs) else {
+    let Ok(derive_meta) = DeriveMeta::from_ast(&context, &input.ident, &input.attrs) else {
         context.check()?;
         unreachable!()
     };
@@ -26,7 +26,7 @@ pub fn expand_serialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>> {
     let serde: Path = parse_quote! { _serde };
     let serializer = format_ident!("serializer");
 
-    let Ok(serialization_block) = (match data {
+    let Ok((serialization_block, field_bounds)) = (match data {
         Data::Struct(d) => {
             if derive_meta.prost_type == ProstType::Message {
                 expand_struct(&context, &derive_meta, &serde, &serializer, ident, d)
@@ -43,6 +43,7 @@ pub fn expand_serialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>> {
                 || derive_meta.prost_type == ProstType::Oneof
             {
                 expand_enum(&context, &derive_meta, &serde, &serializer, ident, d)
+                    .map(|block| (block, Vec::new()))
             } else {
                 context.push_error_spanned_by(
                     d.enum_token,
@@ -63,12 +64,28 @@ pub fn expand_serialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>> {
         unreachable!();
     };
 
-    let oneof_field_name_method = expand_oneof_field_name_method(&derive_meta, ident, data);
+    let oneof_methods = expand_oneof_methods(&context, &derive_meta, &serde, ident, data);
+    let enum_style_impl = expand_enum_style_impl(&derive_meta, ident, data);
 
+    let crate_path = &derive_meta.crate_path;
+    let serde_path = &derive_meta.serde_path;
+    // the bounds take a lifetime only to be checked where the message is used, see
+    // `SerializeMessage`
+    let (impl_generics, where_clause) = if field_bounds.is_empty() {
+        (quote! {}, quote! {})
+    } else {
+        (quote! { <'a> }, quote! { where #(#field_bounds),* })
+    };
+    // a missing derive of a field type is reported here, at the message
+    let serialize_message = quote_spanned! {ident.span()=>
+        #serde::Serialize::serialize(self, #serializer)
+    };
     let impl_body = quote! {
-        extern crate serde as _serde;
+        use #serde_path as _serde;
+        #[allow(unused_imports)]
+        use #crate_path as _prost_serde_derive;
 
-        impl #serde::Serialize for #ident {
+        impl #impl_generics #serde::Serialize for #ident #where_clause {
             fn serialize<S>(&self, #serializer: S) -> Result<S::Ok, S::Error>
             where S: #serde::Serializer,
             {
@@ -77,8 +94,19 @@ pub fn expand_serialize(input: DeriveInput) -> Result<TokenStream, Vec<Error>> {
 
         }
 
-        #oneof_field_name_method
+        impl<'a> _prost_serde_derive::__private::SerializeMessage<'a> for #ident {
+            fn serialize_message<S>(&self, #serializer: S) -> Result<S::Ok, S::Error>
+            where S: #serde::Serializer,
+            {
+                #serialize_message
+            }
+        }
+
+        #oneof_methods
+        #enum_style_impl
     };
 
+    context.check()?;
+
     Ok(wrap_block(impl_body))
 }
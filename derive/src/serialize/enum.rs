@@ -1,39 +1,76 @@
-use convert_case::{Case, Casing};
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::ext::IdentExt;
 use syn::{Data, DataEnum, Ident, Path};
 
-use crate::attr::{DeriveMeta, ProstType};
+use crate::attr::{DeriveMeta, FieldMeta, ProstAttr, ProstType, ProtobufType};
 use crate::context::Context;
+use crate::serialize::field::{get_bytes_as, get_enum_as};
+use crate::util::to_json_name;
 
 pub fn expand_enum(
-    _context: &Context,
+    context: &Context,
     derive_meta: &DeriveMeta,
     _serde: &Path,
     serializer: &Ident,
-    _ident: &Ident,
+    ident: &Ident,
     data: &DataEnum,
 ) -> Result<TokenStream, ()> {
     match derive_meta.prost_type {
-        ProstType::Enum => Ok(quote! {
-            #serializer.serialize_str(self.as_str_name())
-        }),
+        ProstType::Enum => {
+            let name = match get_styled_name_match(derive_meta, data, quote! { self }) {
+                Some(name_match) => quote! { #name_match },
+                None => quote! { self.as_str_name() },
+            };
+            Ok(quote! {
+                if #serializer.is_human_readable() && !_prost_serde_derive::__private::enums_as_integers() {
+                    #serializer.serialize_str(#name)
+                } else {
+                    #serializer.serialize_i32(*self as i32)
+                }
+            })
+        }
         ProstType::Oneof => {
-            let mut match_arms = Vec::new();
-            for variant in data.variants.iter() {
+            let name = ident.to_string();
+            let mut compact_match_arms = Vec::new();
+            let mut has_error = false;
+            for (index, variant) in data.variants.iter().enumerate() {
                 let ident_variant = &variant.ident;
-                let variant = ident_variant.to_string().to_case(Case::Snake);
-                match_arms.push(quote! {
+                let Ok(prost_attr) = ProstAttr::from_ast(context, ident_variant, &variant.attrs)
+                else {
+                    has_error = true;
+                    continue;
+                };
+                // bytes are written as native byte strings in non-human-readable formats
+                let compact_value = match prost_attr.ty {
+                    ProtobufType::Bytes => {
+                        quote! { &_prost_serde_derive::__private::serialize_bytes(v) }
+                    }
+                    _ => quote! { v },
+                };
+                let variant = derive_meta.get_variant_name(ident_variant);
+                let index = index as u32;
+                compact_match_arms.push(quote! {
                     Self::#ident_variant(ref v) => {
-                        #serializer.serialize_newtype_struct(#variant, v)
+                        #serializer.serialize_newtype_variant(#name, #index, #variant, #compact_value)
                     }
                 });
             }
+            if has_error {
+                return Err(());
+            }
 
+            // the oneof is written on its own with the default base64 encoder
             Ok(quote! {
-                match self {
-                    #(#match_arms)*
+                if #serializer.is_human_readable() {
+                    _prost_serde_derive::__private::SerializeMember::serialize_member(
+                        self,
+                        #serializer,
+                        _prost_serde_derive::__private::encode_base64,
+                    )
+                } else {
+                    match self {
+                        #(#compact_match_arms)*
+                    }
                 }
             })
         }
@@ -41,22 +78,67 @@ pub fn expand_enum(
     }
 }
 
-pub fn expand_oneof_field_name_method(
+pub fn expand_oneof_methods(
+    context: &Context,
     derive_meta: &DeriveMeta,
+    serde: &Path,
     ident: &Ident,
     data: &Data,
 ) -> TokenStream {
     if let Data::Enum(d) = data {
         if derive_meta.prost_type == ProstType::Oneof {
             let mut match_arms = Vec::new();
+            let mut json_match_arms = Vec::new();
+            let mut member_match_arms = Vec::new();
             for variant in d.variants.iter() {
                 let ident_variant = &variant.ident;
-                let variant = ident_variant.unraw().to_string().to_case(Case::Snake);
+                let Ok(prost_attr) = ProstAttr::from_ast(context, ident_variant, &variant.attrs)
+                else {
+                    return quote! {};
+                };
+                let field_meta = FieldMeta::from_ast(
+                    context,
+                    derive_meta,
+                    ident_variant,
+                    &prost_attr,
+                    &variant.attrs,
+                );
+                // the members are written like fields of the same types
+                let serialize_as = match prost_attr.ty {
+                    ProtobufType::Enumeration(ref path) => Some(get_enum_as(path)),
+                    ProtobufType::Bytes => Some(get_bytes_as(
+                        field_meta.bytes_encoding,
+                        quote! { encode_base64 },
+                    )),
+                    ProtobufType::Float | ProtobufType::Double => Some(quote! {
+                        _prost_serde_derive::__private::FloatAs
+                    }),
+                    _ => None,
+                };
+                let value = match serialize_as {
+                    Some(serialize_as) => {
+                        quote! { &_prost_serde_derive::__private::Single(v, #serialize_as) }
+                    }
+                    None => quote! { v },
+                };
+                let variant = derive_meta.get_variant_name(ident_variant);
+                member_match_arms.push(quote! {
+                    Self::#ident_variant(ref v) => serializer.serialize_newtype_struct(#variant, #value),
+                });
+                let json_variant = match derive_meta.rename_all_variants {
+                    Some(_) => variant.clone(),
+                    None => to_json_name(&variant),
+                };
                 match_arms.push(quote! {
                     Self::#ident_variant(_) => {
                         #variant
                     }
                 });
+                json_match_arms.push(quote! {
+                    Self::#ident_variant(_) => {
+                        #json_variant
+                    }
+                });
             }
 
             return quote! {
@@ -66,6 +148,28 @@ pub fn expand_oneof_field_name_method(
                             #(#match_arms)*
                         }
                     }
+
+                    #[doc(hidden)]
+                    pub fn json_field_name(&self) -> &'static str {
+                        match self {
+                            #(#json_match_arms)*
+                        }
+                    }
+                }
+
+                impl _prost_serde_derive::__private::SerializeMember for #ident {
+                    fn serialize_member<S>(
+                        &self,
+                        serializer: S,
+                        encode_base64: fn(&[u8], &mut ::std::fmt::Formatter) -> ::std::fmt::Result,
+                    ) -> Result<S::Ok, S::Error>
+                    where
+                        S: #serde::Serializer,
+                    {
+                        match self {
+                            #(#member_match_arms)*
+                        }
+                    }
                 }
             };
         }
@@ -73,3 +177,48 @@ pub fn expand_oneof_field_name_method(
 
     quote! {}
 }
+
+/// Returns a match of `value` to the names of the values in the style of the enum, or `None` if
+/// the enum keeps the names in the proto.
+fn get_styled_name_match(
+    derive_meta: &DeriveMeta,
+    data: &DataEnum,
+    value: TokenStream,
+) -> Option<TokenStream> {
+    let match_arms = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let ident_variant = &variant.ident;
+            let name = derive_meta.enum_style.get_name(ident_variant)?;
+            Some(quote! { Self::#ident_variant => #name, })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(quote! {
+        match #value {
+            #(#match_arms)*
+        }
+    })
+}
+
+/// Lets fields of the enum, which only know its path, write the names in its style.
+pub fn expand_enum_style_impl(derive_meta: &DeriveMeta, ident: &Ident, data: &Data) -> TokenStream {
+    if let Data::Enum(d) = data {
+        if derive_meta.prost_type == ProstType::Enum {
+            if let Some(name_match) = get_styled_name_match(derive_meta, d, quote! { v }) {
+                return quote! {
+                    impl _prost_serde_derive::__private::StyledEnumNames for #ident {
+                        fn name(value: i32) -> Option<&'static str> {
+                            <#ident as ::core::convert::TryFrom<i32>>::try_from(value)
+                                .ok()
+                                .map(|v| #name_match)
+                        }
+                    }
+                };
+            }
+        }
+    }
+
+    quote! {}
+}
@@ -1,81 +1,156 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::ext::IdentExt;
-use syn::{Field, Path};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Field, Path};
 
-use crate::attr::{FieldModifier, ProstAttr, ProtobufType};
+use crate::attr::{BytesEncoding, DeriveMeta, FieldMeta, FieldModifier, ProstAttr, ProtobufType};
 use crate::context::Context;
+use crate::util::to_json_name;
 
-pub fn serialize_field(context: &Context, serde: &Path, field: &Field) -> Result<TokenStream, ()> {
+/// Returns the writer of an enum value, which writes the names in the style of the enum.
+pub fn get_enum_as(path: &Path) -> TokenStream {
+    quote! {
+        _prost_serde_derive::__private::EnumAs({
+            use _prost_serde_derive::__private::{ProtoNames as _, StyledNames as _};
+            (&_prost_serde_derive::__private::EnumStyle::<#path>::new())
+                .names(|v| {
+                    <#path as ::core::convert::TryFrom<i32>>::try_from(v)
+                        .ok()
+                        .map(|v| v.as_str_name())
+                })
+        })
+    }
+}
+
+/// Returns the writer of a `bytes` value in the encoding of the field. `encode_base64` is the
+/// expression of the base64 encoder of the message.
+pub fn get_bytes_as(encoding: BytesEncoding, encode_base64: TokenStream) -> TokenStream {
+    match encoding {
+        BytesEncoding::Base64 => quote! {
+            _prost_serde_derive::__private::BytesAs(#encode_base64)
+        },
+        BytesEncoding::Base64Url => quote! {
+            _prost_serde_derive::__private::BytesAs(
+                _prost_serde_derive::__private::encode_base64_url_no_pad,
+            )
+        },
+        BytesEncoding::Hex => quote! {
+            _prost_serde_derive::__private::BytesAs(_prost_serde_derive::__private::encode_hex)
+        },
+        BytesEncoding::Array => quote! { _prost_serde_derive::__private::ByteArrayAs },
+    }
+}
+
+pub struct SerializeFieldTokenStream {
+    pub serialize_stmt: TokenStream,
+    pub compact_serialize_stmt: TokenStream,
+    pub field_bound: Option<TokenStream>,
+}
+
+pub fn serialize_field(
+    context: &Context,
+    meta: &DeriveMeta,
+    field: &Field,
+) -> Result<SerializeFieldTokenStream, ()> {
     let ident = field.ident.as_ref().ok_or_else(|| {
         context.push_error_spanned_by(field, "Field should have a name");
     })?;
-    let ident_str = ident.unraw().to_string();
+    let prost_attr = ProstAttr::from_ast(context, ident, &field.attrs)?;
+    let field_meta = FieldMeta::from_ast(context, meta, ident, &prost_attr, &field.attrs);
+    let ident_str = field_meta.get_name(ident);
+    let field_bound = prost_attr.get_field_bound(
+        &field.ty,
+        &format_ident!("SerializeMessage"),
+        &parse_quote! { 'a },
+    );
 
-    let prost_attr = ProstAttr::from_ast(context, &field.attrs)?;
+    // enums, bytes and floats are written by runtime helpers, which pick the representation by the
+    // format, and messages by the helper bounded in the where clause of the impl
+    let serialize_as = match prost_attr.ty {
+        ProtobufType::Enumeration(ref p) => Some(get_enum_as(p)),
+        ProtobufType::Bytes => {
+            let encoder = meta.base64_output.get_encoder();
+            Some(get_bytes_as(
+                field_meta.bytes_encoding,
+                quote! { _prost_serde_derive::__private::#encoder },
+            ))
+        }
+        ProtobufType::Float | ProtobufType::Double => Some(quote! {
+            _prost_serde_derive::__private::FloatAs
+        }),
+        ProtobufType::Message | ProtobufType::OneOf(_) => Some(quote! {
+            _prost_serde_derive::__private::MessageAs
+        }),
+        _ => None,
+    };
+    let value_expr = match serialize_as {
+        Some(serialize_as) => {
+            let wrapper = match prost_attr.modifier {
+                FieldModifier::None => quote! { Single },
+                FieldModifier::Optional => quote! { Optional },
+                FieldModifier::Repeated => quote! { Repeated },
+            };
+            quote! { &_prost_serde_derive::__private::#wrapper(&self.#ident, #serialize_as) }
+        }
+        None => quote! { &self.#ident },
+    };
+
+    // every field is written in declaration order in non-human-readable formats, so that
+    // positional formats can read them back
+    let compact_serialize_stmt = quote! {
+        state.serialize_field(#ident_str, #value_expr)?;
+    };
 
-    // it is a special case that the field should be flatten.
+    // it is a special case that the field should be flatten. The member is written with the
+    // base64 encoder of this message.
     if let ProtobufType::OneOf(_) = prost_attr.ty {
-        return Ok(quote! {
-            if let Some(v) = &self.#ident {
-                state.serialize_field(v.field_name(), &self.#ident)?;
-            }
+        let encoder = meta.base64_output.get_encoder();
+        let encode_base64 = quote! { _prost_serde_derive::__private::#encoder };
+        return Ok(SerializeFieldTokenStream {
+            serialize_stmt: quote! {
+                if let Some(v) = &self.#ident {
+                    let name = if json_names { v.json_field_name() } else { v.field_name() };
+                    state.serialize_field(
+                        name,
+                        &_prost_serde_derive::__private::Member(v, #encode_base64),
+                    )?;
+                }
+            },
+            compact_serialize_stmt,
+            field_bound,
         });
     }
 
-    let serialize_stmt = match prost_attr.ty {
-        ProtobufType::Bytes(_) => {
-            let base64 = quote! { extern crate base64 as _base64; };
-            match prost_attr.modifier {
-                FieldModifier::Repeated => {
-                    quote! {
-                        #base64
-                        &self.#ident.iter().map(_base64::encode).collect::<Vec<_>>()
-                    }
-                }
-                FieldModifier::Optional => {
-                    quote! {
-                        #base64
-                        &self.#ident.as_ref().map(_base64::encode)
-                    }
-                }
-                FieldModifier::None => {
-                    quote! {
-                        #base64
-                        &_base64::encode(&self.#ident)
-                    }
-                }
-            }
+    // `emit_defaults` and `json_names` are set by the caller from the print options, a renamed
+    // field keeps its name regardless
+    let json_name = to_json_name(&ident_str);
+    let name = if field_meta.rename.is_none() && json_name != ident_str {
+        quote! { if json_names { #json_name } else { #ident_str } }
+    } else {
+        quote! { #ident_str }
+    };
+    let is_default_expr = match (&prost_attr.ty, &prost_attr.modifier) {
+        (ProtobufType::Enumeration(_), FieldModifier::None) => {
+            let default_value = prost_attr.get_default_value();
+            quote! { self.#ident == #default_value }
         }
-        ProtobufType::Enumeration(p) => match prost_attr.modifier {
-            FieldModifier::Repeated => quote! {
-                &self.#ident.iter().map(|v| match #p::from_i32(*v) {
-                    Some(v) => Ok(v.as_str_name()),
-                    None => Err(#serde::ser::Error::custom(format!("Invalid enum value {}", v))),
-                }).collect::<Result<Vec<_>, _>>()?
-            },
-            FieldModifier::Optional => quote! {
-                &match self.#ident {
-                    Some(v) => {
-                        Some(#p::from_i32(v).ok_or_else(
-                            || #serde::ser::Error::custom(format!("Invalid enum value {}", v))
-                        )?.as_str_name())
-                    },
-                    None => None,
-                }
-            },
-            FieldModifier::None => quote! {
-                #p::from_i32(self.#ident).ok_or_else(
-                    || #serde::ser::Error::custom(format!("Invalid enum value {}", self.#ident))
-                )?.as_str_name()
-            },
-        },
-        _ => quote! {
-            &self.#ident
-        },
+        _ => quote! { _prost_serde_derive::__private::is_default(&self.#ident) },
     };
 
-    Ok(quote! {
-        state.serialize_field(#ident_str, { #serialize_stmt })?;
+    let emit_default = if field_meta.skip_serializing_if_default {
+        quote! { false }
+    } else {
+        quote! { emit_defaults }
+    };
+
+    Ok(SerializeFieldTokenStream {
+        serialize_stmt: quote! {
+            if #emit_default || !(#is_default_expr) {
+                state.serialize_field(#name, #value_expr)?;
+            } else {
+                state.skip_field(#name)?;
+            }
+        },
+        compact_serialize_stmt,
+        field_bound,
     })
 }
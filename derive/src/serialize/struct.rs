@@ -1,35 +1,53 @@
+use itertools::Itertools;
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 use syn::{DataStruct, Fields, Path};
 
-use super::field::serialize_field;
+use super::field::{serialize_field, SerializeFieldTokenStream};
 use crate::attr::DeriveMeta;
 use crate::context::Context;
 
 pub fn expand_struct(
     context: &Context,
-    _meta: &DeriveMeta,
+    meta: &DeriveMeta,
     serde: &Path,
     serializer: &Ident,
     ident: &Ident,
     data: &DataStruct,
-) -> Result<TokenStream, ()> {
+) -> Result<(TokenStream, Vec<TokenStream>), ()> {
     match &data.fields {
         Fields::Named(f) => {
             let name = ident.to_string();
-            let fields = f
+            let (fields, errors): (Vec<SerializeFieldTokenStream>, Vec<()>) = f
                 .named
                 .iter()
-                .map(|v| serialize_field(context, serde, v))
-                .collect::<Result<Vec<TokenStream>, ()>>()?;
+                .map(|v| serialize_field(context, meta, v))
+                .partition_result();
+            if !errors.is_empty() {
+                return Err(());
+            }
             let count = f.named.len();
-            Ok(quote! {
+            let serialize_stmts = fields.iter().map(|v| &v.serialize_stmt);
+            let compact_serialize_stmts = fields.iter().map(|v| &v.compact_serialize_stmt);
+            let field_bounds = fields
+                .iter()
+                .filter_map(|v| v.field_bound.clone())
+                .collect();
+            let serialize_block = quote! {
                 use #serde::ser::SerializeStruct;
 
+                let is_human_readable = #serializer.is_human_readable();
                 let mut state = #serializer.serialize_struct(#name, #count)?;
-                #(#fields)*
+                if is_human_readable {
+                    let emit_defaults = _prost_serde_derive::__private::emit_defaults();
+                    let json_names = _prost_serde_derive::__private::json_names();
+                    #(#serialize_stmts)*
+                } else {
+                    #(#compact_serialize_stmts)*
+                }
                 state.end()
-            })
+            };
+            Ok((serialize_block, field_bounds))
         }
         Fields::Unnamed(_) => {
             context.push_error_spanned_by(
@@ -1,10 +1,18 @@
+use std::collections::BTreeMap;
 use std::fmt::Display;
 
+use convert_case::{Boundary, Case, Converter};
 use extend::ext;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
 use syn::punctuated::Punctuated;
-use syn::{Attribute, Ident, Meta, Path, Token};
+use syn::{
+    Attribute, Expr, ExprLit, GenericArgument, Ident, Lit, LitByte, LitByteStr, Meta, Path,
+    PathArguments, Token, Type,
+};
+
+use crate::context::Context;
 
 pub fn into_syn_error<A: ToTokens, T: Display>(obj: A, msg: T) -> syn::Error {
     syn::Error::new_spanned(obj.into_token_stream(), msg)
@@ -21,26 +29,98 @@ pub fn wrap_block(code: TokenStream) -> TokenStream {
 }
 
 pub fn parse_meta_args_from_attrs(
+    context: &Context,
     attrs: &[Attribute],
     ident: &Ident,
     is_strict: bool,
-) -> Result<Vec<Meta>, syn::Error> {
+) -> Vec<Meta> {
     let mut meta_args = Vec::new();
     for attr in attrs.iter() {
         if attr.meta.path().is_ident(ident) {
             if let Meta::List(meta_list) = &attr.meta {
-                meta_args.extend(
-                    meta_list
-                        .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?
-                        .into_iter(),
-                );
+                if let Some(args) = context.ok_or_push(
+                    meta_list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated),
+                ) {
+                    meta_args.extend(args);
+                }
             } else if is_strict {
-                return Err(into_syn_error(&attr.meta, "is not a structured list"));
+                context.push_error_spanned_by(
+                    &attr.meta,
+                    format!("is not a structured list, expected `#[{}(...)]`", ident),
+                );
             }
         }
     }
 
-    Ok(meta_args)
+    meta_args
+}
+
+pub fn parse_lit_path(value: &Expr) -> Result<Path, syn::Error> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => lit
+            .parse()
+            .map_err(|_| into_syn_error(lit, "should be a path, e.g. `\"::path::to::crate\"`")),
+        _ => Err(into_syn_error(value, "should be a string literal")),
+    }
+}
+
+pub fn parse_lit_str(value: &Expr) -> Result<String, syn::Error> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(lit.value()),
+        _ => Err(into_syn_error(value, "should be a string literal")),
+    }
+}
+
+/// Parses a case convention of `rename_all`, named like the ones of serde.
+pub fn parse_lit_case(value: &Expr) -> Result<Case, syn::Error> {
+    match &parse_lit_str(value)?[..] {
+        "camelCase" => Ok(Case::Camel),
+        "PascalCase" => Ok(Case::Pascal),
+        "snake_case" => Ok(Case::Snake),
+        "SCREAMING_SNAKE_CASE" => Ok(Case::ScreamingSnake),
+        "kebab-case" => Ok(Case::Kebab),
+        "SCREAMING-KEBAB-CASE" => Ok(Case::Cobol),
+        _ => Err(into_syn_error(
+            value,
+            "unrecognized case, expected `\"camelCase\"`, `\"PascalCase\"`, `\"snake_case\"`, \
+             `\"SCREAMING_SNAKE_CASE\"`, `\"kebab-case\"` or `\"SCREAMING-KEBAB-CASE\"`",
+        )),
+    }
+}
+
+/// Converts an ident generated by prost-build back to the case of protobuf names, e.g.
+/// `Http2Server` to `HTTP2_SERVER`. Unlike `to_case()`, digits stay attached to the preceding word.
+pub fn convert_ident_case(name: &str, case: Case) -> String {
+    Converter::new()
+        .set_boundaries(&[
+            Boundary::LowerUpper,
+            Boundary::DigitUpper,
+            Boundary::Acronym,
+        ])
+        .to_case(case)
+        .convert(name)
+}
+
+/// Converts a field ident, which is in snake_case, to another case. Only underscores separate the
+/// words, so digits stay attached as in the JSON names, e.g. `address_v2` to `address-v2`.
+pub fn convert_field_case(name: &str, case: Case) -> String {
+    Converter::new()
+        .set_boundaries(&[Boundary::Underscore])
+        .to_case(case)
+        .convert(name)
+}
+
+/// Returns the prefix of the value names of an enum in the proto, which prost-build strips from
+/// the variants, e.g. `LANGUAGE_` for `Language`.
+pub fn get_enum_prefix(ident: &Ident) -> String {
+    format!(
+        "{}_",
+        convert_ident_case(&ident.unraw().to_string(), Case::ScreamingSnake)
+    )
 }
 
 pub fn set_option_or_err<T, A: ToTokens>(
@@ -56,6 +136,88 @@ pub fn set_option_or_err<T, A: ToTokens>(
     }
 }
 
+/// Strips the containers(`Option`, `Box` and `Vec`) prost wraps message types with.
+pub fn get_message_type(ty: &Type) -> &Type {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if ["Option", "Box", "Vec"].iter().any(|v| segment.ident == v) {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                        return get_message_type(ty);
+                    }
+                }
+            }
+        }
+    }
+
+    ty
+}
+
+/// Expands a lookup running the statement paired with the name that `value: &[u8]` equals, if
+/// any. Candidates are narrowed down by length and first byte before comparing whole names.
+pub fn expand_name_match(value: &Ident, arms: Vec<(String, TokenStream)>) -> TokenStream {
+    let mut groups = BTreeMap::<usize, BTreeMap<u8, Vec<(String, TokenStream)>>>::new();
+    for (name, stmt) in arms {
+        // protobuf names are never empty, nor are renames and aliases
+        let first = name.as_bytes()[0];
+        groups
+            .entry(name.len())
+            .or_default()
+            .entry(first)
+            .or_default()
+            .push((name, stmt));
+    }
+
+    let len_arms = groups.into_iter().map(|(len, group)| {
+        let first_arms = group.into_iter().map(|(first, arms)| {
+            let first = LitByte::new(first, Span::call_site());
+            let compares = arms.into_iter().map(|(name, stmt)| {
+                let name = LitByteStr::new(name.as_bytes(), Span::call_site());
+                quote! {
+                    if #value == #name {
+                        #stmt
+                    }
+                }
+            });
+            quote! {
+                #first => {
+                    #(#compares)*
+                }
+            }
+        });
+        quote! {
+            #len => match #value[0] {
+                #(#first_arms)*
+                _ => {}
+            }
+        }
+    });
+
+    quote! {
+        match #value.len() {
+            #(#len_arms)*
+            _ => {}
+        }
+    }
+}
+
+/// Converts the name of a field to its JSON name in lowerCamelCase, the way protoc does.
+pub fn to_json_name(name: &str) -> String {
+    let mut json_name = String::with_capacity(name.len());
+    let mut capitalize_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize_next = true;
+        } else if capitalize_next {
+            json_name.push(c.to_ascii_uppercase());
+            capitalize_next = false;
+        } else {
+            json_name.push(c);
+        }
+    }
+    json_name
+}
+
 #[ext]
 pub impl Path {
     #[inline]
@@ -0,0 +1,1051 @@
+use std::borrow::BorrowMut;
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+
+use base64::display::Base64Display;
+use base64::engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::{alphabet, Engine as _};
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeSeed, SeqAccess, Unexpected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{options, report};
+
+pub struct FieldScope {
+    entered: bool,
+}
+
+impl FieldScope {
+    #[inline]
+    pub fn enter(name: &str) -> Self {
+        Self {
+            entered: report::enter(name),
+        }
+    }
+}
+
+impl Drop for FieldScope {
+    #[inline]
+    fn drop(&mut self) {
+        if self.entered {
+            report::leave();
+        }
+    }
+}
+
+#[inline]
+pub fn report_omitted<E: Display>(name: &str, error: &E) {
+    report::omit(name, error);
+}
+
+/// Returns the parse option of the current call, or the attribute of the message when the option
+/// is not set.
+#[inline]
+pub fn ignore_unknown_fields(attribute: bool) -> bool {
+    options::parse_options()
+        .ignore_unknown_fields
+        .unwrap_or(attribute)
+}
+
+#[inline]
+pub fn omit_type_errors(attribute: bool) -> bool {
+    options::parse_options()
+        .omit_type_errors
+        .unwrap_or(attribute)
+}
+
+#[inline]
+pub fn use_default_for_missing_fields(attribute: bool) -> bool {
+    options::parse_options()
+        .use_default_for_missing_fields
+        .unwrap_or(attribute)
+}
+
+#[inline]
+pub fn lenient_enums(attribute: bool) -> bool {
+    options::parse_options().lenient_enums.unwrap_or(attribute)
+}
+
+#[inline]
+pub fn string_input(attribute: bool) -> bool {
+    options::parse_options().string_input.unwrap_or(attribute)
+}
+
+#[inline]
+pub fn emit_defaults() -> bool {
+    options::print_options().emit_defaults
+}
+
+#[inline]
+pub fn json_names() -> bool {
+    options::print_options().json_names
+}
+
+#[inline]
+pub fn enums_as_integers() -> bool {
+    options::print_options().enums_as_integers
+}
+
+#[inline]
+pub fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+/// Implemented by every type deriving `Deserialize`. Messages bound their message fields by it
+/// rather than by `Deserialize`, so that bounds between recursive messages resolve by the header of
+/// this impl alone, and a missing derive is reported once by the impl of the message.
+#[diagnostic::on_unimplemented(
+    message = "the message type `{Self}` does not implement `Deserialize`",
+    label = "required by a message field of this type",
+    note = "add `#[derive(prost_serde_derive::Deserialize)]` to `{Self}`, e.g. with `type_attribute()` of prost-build"
+)]
+pub trait DeserializeMessage<'de>: Deserialize<'de> {}
+
+/// Implemented by every type deriving `Serialize`, see `DeserializeMessage`. `Serialize` takes no
+/// lifetime, so the message fields are written by this trait instead of a supertrait, which would
+/// have the bounds checked before the message is used.
+#[diagnostic::on_unimplemented(
+    message = "the message type `{Self}` does not implement `Serialize`",
+    label = "required by a message field of this type",
+    note = "add `#[derive(prost_serde_derive::Serialize)]` to `{Self}`, e.g. with `type_attribute()` of prost-build"
+)]
+pub trait SerializeMessage<'a> {
+    fn serialize_message<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+impl<'a, T: SerializeMessage<'a> + ?Sized> SerializeMessage<'a> for Box<T> {
+    #[inline]
+    fn serialize_message<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize_message(serializer)
+    }
+}
+
+impl<'a, T: SerializeMessage<'a>> SerializeMessage<'a> for Option<T> {
+    #[inline]
+    fn serialize_message<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&Single(value, MessageAs)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Serializes a `bytes` field as a native byte string in non-human-readable formats.
+pub struct SerializeBytes<'a>(&'a [u8]);
+
+#[inline]
+pub fn serialize_bytes<T: AsRef<[u8]>>(value: &T) -> SerializeBytes<'_> {
+    SerializeBytes(value.as_ref())
+}
+
+impl Serialize for SerializeBytes<'_> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Deserializes a `bytes` field from a native byte string in non-human-readable formats.
+pub struct DeserializeBytes(pub Vec<u8>);
+
+impl<'de> Deserialize<'de> for DeserializeBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = DeserializeBytes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte string")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(DeserializeBytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(DeserializeBytes(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(DeserializeBytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Deserializes a value in place, reusing the allocations of `T`.
+pub struct InPlaceSeed<'a, T>(pub &'a mut T);
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for InPlaceSeed<'_, T> {
+    type Value = ();
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        T::deserialize_in_place(deserializer, self.0)
+    }
+}
+
+/// Deserializes an optional value in place. The value already in `place` is reused, and a new one
+/// is converted with `T: From<M>` (e.g. boxed) when there is none.
+pub struct OptionInPlaceSeed<'a, T, M> {
+    place: &'a mut Option<T>,
+    marker: PhantomData<M>,
+}
+
+impl<'a, T, M> OptionInPlaceSeed<'a, T, M> {
+    #[inline]
+    pub fn new(place: &'a mut Option<T>) -> Self {
+        Self {
+            place,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, M> DeserializeSeed<'de> for OptionInPlaceSeed<'_, T, M>
+where
+    T: BorrowMut<M> + From<M>,
+    M: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, T, M> Visitor<'de> for OptionInPlaceSeed<'_, T, M>
+where
+    T: BorrowMut<M> + From<M>,
+    M: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("option")
+    }
+
+    #[inline]
+    fn visit_none<E: serde::de::Error>(self) -> Result<(), E> {
+        *self.place = None;
+        Ok(())
+    }
+
+    #[inline]
+    fn visit_unit<E: serde::de::Error>(self) -> Result<(), E> {
+        *self.place = None;
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        match self.place {
+            Some(value) => M::deserialize_in_place(deserializer, value.borrow_mut()),
+            None => {
+                *self.place = Some(M::deserialize(deserializer)?.into());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Names of the values of an enum with `enum_style`, implemented by its `Serialize` derive.
+pub trait StyledEnumNames {
+    fn name(value: i32) -> Option<&'static str>;
+}
+
+/// Values of an enum with `enum_style` by their names, implemented by its `Deserialize` derive.
+pub trait StyledEnumValues {
+    fn value(name: &str) -> Option<i32>;
+}
+
+/// Picks the styled names of the enum `T` when it has them, or the given proto names otherwise,
+/// so that fields can refer to enums which do not derive the traits of this crate.
+///
+/// `(&EnumStyle::<T>::new()).names(proto)` resolves to [`StyledNames`] when `T` implements
+/// [`StyledEnumNames`], and falls back to [`ProtoNames`] through autoref otherwise. The same goes
+/// for `values`.
+pub struct EnumStyle<T>(PhantomData<fn() -> T>);
+
+impl<T> EnumStyle<T> {
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+pub trait StyledNames {
+    fn names(&self, proto: fn(i32) -> Option<&'static str>) -> fn(i32) -> Option<&'static str>;
+}
+
+impl<T: StyledEnumNames> StyledNames for EnumStyle<T> {
+    #[inline]
+    fn names(&self, _: fn(i32) -> Option<&'static str>) -> fn(i32) -> Option<&'static str> {
+        T::name
+    }
+}
+
+pub trait ProtoNames {
+    fn names(&self, proto: fn(i32) -> Option<&'static str>) -> fn(i32) -> Option<&'static str>;
+}
+
+impl<T> ProtoNames for &EnumStyle<T> {
+    #[inline]
+    fn names(&self, proto: fn(i32) -> Option<&'static str>) -> fn(i32) -> Option<&'static str> {
+        proto
+    }
+}
+
+pub trait StyledValues {
+    fn values(&self, proto: fn(&str) -> Option<i32>) -> fn(&str) -> Option<i32>;
+}
+
+impl<T: StyledEnumValues> StyledValues for EnumStyle<T> {
+    #[inline]
+    fn values(&self, _: fn(&str) -> Option<i32>) -> fn(&str) -> Option<i32> {
+        T::value
+    }
+}
+
+pub trait ProtoValues {
+    fn values(&self, proto: fn(&str) -> Option<i32>) -> fn(&str) -> Option<i32>;
+}
+
+impl<T> ProtoValues for &EnumStyle<T> {
+    #[inline]
+    fn values(&self, proto: fn(&str) -> Option<i32>) -> fn(&str) -> Option<i32> {
+        proto
+    }
+}
+
+/// Matches `name` to an enum value case-insensitively, with or without the `prefix` of the enum,
+/// by the function mapping the names in the proto to their numbers.
+///
+/// The names in the proto are assumed to be in uppercase, as the style guide of protobuf tells.
+/// Fails if the name matches different values with and without the prefix.
+pub fn parse_enum_leniently<E: serde::de::Error>(
+    name: &str,
+    prefix: &str,
+    from_proto_name: fn(&str) -> Option<i32>,
+) -> Result<Option<i32>, E> {
+    let name = name.to_ascii_uppercase();
+    let prefixed_name = format!("{}{}", prefix, name);
+    match (from_proto_name(&name), from_proto_name(&prefixed_name)) {
+        (Some(value), Some(prefixed_value)) if value != prefixed_value => {
+            Err(E::custom(format_args!(
+                "ambiguous enum value, matches both `{}` and `{}`",
+                name, prefixed_name
+            )))
+        }
+        (value, prefixed_value) => Ok(value.or(prefixed_value)),
+    }
+}
+
+/// Deserializes the value of an enumeration field by its name without allocating, or by its
+/// number, which non-human-readable formats always use. Unknown names and numbers are `None` when
+/// they are ignored.
+#[derive(Clone, Copy)]
+pub struct EnumSeed {
+    /// Maps a name to its number, like `from_str_name` of the prost enum, or the name in the
+    /// style of the enum.
+    pub values: fn(&str) -> Option<i32>,
+    /// Maps a name in the proto to its number, for the lenient parsing.
+    pub proto_values: fn(&str) -> Option<i32>,
+    /// Whether a number is a value of the enum, like `TryFrom<i32>` of the prost enum.
+    pub is_valid: fn(i32) -> bool,
+    /// The prefix of the names in the proto, e.g. `LANGUAGE_` of `LANGUAGE_ENGLISH`.
+    pub prefix: &'static str,
+    /// Whether the name is matched by [`parse_enum_leniently`] when it does not match exactly.
+    pub lenient: bool,
+    /// Whether unknown names are ignored along with unknown fields, as the protobuf JSON mapping
+    /// tells.
+    pub ignore_unknown: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for EnumSeed {
+    type Value = Option<i32>;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Option<i32>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(self)
+        } else {
+            i32::deserialize(deserializer).map(Some)
+        }
+    }
+}
+
+impl Visitor<'_> for EnumSeed {
+    type Value = Option<i32>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an enum value name or number")
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Option<i32>, E> {
+        match i32::try_from(v) {
+            Ok(value) if (self.is_valid)(value) => Ok(Some(value)),
+            _ if self.ignore_unknown => Ok(None),
+            _ => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Option<i32>, E> {
+        match i32::try_from(v) {
+            Ok(value) if (self.is_valid)(value) => Ok(Some(value)),
+            _ if self.ignore_unknown => Ok(None),
+            _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    #[inline]
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Option<i32>, E> {
+        if let Some(value) = (self.values)(v) {
+            return Ok(Some(value));
+        }
+        if self.lenient {
+            if let Some(value) = parse_enum_leniently(v, self.prefix, self.proto_values)? {
+                return Ok(Some(value));
+            }
+        }
+        if self.ignore_unknown {
+            Ok(None)
+        } else {
+            Err(E::unknown_variant(v, &[]))
+        }
+    }
+}
+
+/// Deserializes a `bool` field, also from `"true"` and `"false"` in human-readable formats when
+/// `strings` is set.
+#[derive(Clone, Copy)]
+pub struct BoolSeed {
+    pub strings: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for BoolSeed {
+    type Value = bool;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        if self.strings && deserializer.is_human_readable() {
+            deserializer.deserialize_any(self)
+        } else {
+            bool::deserialize(deserializer)
+        }
+    }
+}
+
+impl Visitor<'_> for BoolSeed {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean")
+    }
+
+    #[inline]
+    fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<bool, E> {
+        Ok(v)
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<bool, E> {
+        match v {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(E::invalid_value(Unexpected::Str(v), &self)),
+        }
+    }
+}
+
+/// Integer and floating point types of protobuf, read from the representations the protobuf JSON
+/// mapping allows in addition to plain numbers.
+pub trait Number: Sized + for<'de> Deserialize<'de> {
+    const EXPECTING: &'static str;
+
+    fn from_i64(value: i64) -> Option<Self>;
+    fn from_u64(value: u64) -> Option<Self>;
+    fn from_f64(value: f64) -> Option<Self>;
+    fn from_str(value: &str) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty),*) => {
+        $(
+            /// Accepts integral floats like `5.0` and `1e3`, and quoted integers, within the range
+            /// of the type.
+            impl Number for $ty {
+                const EXPECTING: &'static str =
+                    concat!("an integer within the range of ", stringify!($ty));
+
+                #[inline]
+                fn from_i64(value: i64) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                #[inline]
+                fn from_u64(value: u64) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                fn from_f64(value: f64) -> Option<Self> {
+                    if value.is_finite() && value.fract() == 0.0 {
+                        // saturates far outside of the range of any of the types
+                        Self::try_from(value as i128).ok()
+                    } else {
+                        None
+                    }
+                }
+
+                fn from_str(value: &str) -> Option<Self> {
+                    match value.parse::<i128>() {
+                        Ok(value) => Self::try_from(value).ok(),
+                        Err(_) => value.parse::<f64>().ok().and_then(Self::from_f64),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i32, u32, i64, u64);
+
+/// Parses the special values written as strings, or a quoted finite number.
+fn parse_float(value: &str) -> Option<f64> {
+    match value {
+        "NaN" => Some(f64::NAN),
+        "Infinity" => Some(f64::INFINITY),
+        "-Infinity" => Some(f64::NEG_INFINITY),
+        // `parse()` accepts other spellings of the special values, like `inf`
+        _ => value.parse::<f64>().ok().filter(|value| value.is_finite()),
+    }
+}
+
+impl Number for f64 {
+    const EXPECTING: &'static str = "a number, `NaN`, `Infinity` or `-Infinity`";
+
+    #[inline]
+    fn from_i64(value: i64) -> Option<Self> {
+        Some(value as f64)
+    }
+
+    #[inline]
+    fn from_u64(value: u64) -> Option<Self> {
+        Some(value as f64)
+    }
+
+    #[inline]
+    fn from_f64(value: f64) -> Option<Self> {
+        Some(value)
+    }
+
+    #[inline]
+    fn from_str(value: &str) -> Option<Self> {
+        parse_float(value)
+    }
+}
+
+impl Number for f32 {
+    const EXPECTING: &'static str =
+        "a number within the range of f32, `NaN`, `Infinity` or `-Infinity`";
+
+    #[inline]
+    fn from_i64(value: i64) -> Option<Self> {
+        Some(value as f32)
+    }
+
+    #[inline]
+    fn from_u64(value: u64) -> Option<Self> {
+        Some(value as f32)
+    }
+
+    fn from_f64(value: f64) -> Option<Self> {
+        if value.is_finite() && value.abs() > f32::MAX as f64 {
+            None
+        } else {
+            Some(value as f32)
+        }
+    }
+
+    fn from_str(value: &str) -> Option<Self> {
+        parse_float(value).and_then(Self::from_f64)
+    }
+}
+
+/// Deserializes an integer or floating point field by [`Number`] in human-readable formats, or as
+/// it is in non-human-readable formats.
+pub struct NumberSeed<T>(PhantomData<fn() -> T>);
+
+impl<T> NumberSeed<T> {
+    #[inline]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Clone for NumberSeed<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NumberSeed<T> {}
+
+impl<'de, T: Number> DeserializeSeed<'de> for NumberSeed<T> {
+    type Value = T;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(self)
+        } else {
+            T::deserialize(deserializer)
+        }
+    }
+}
+
+impl<T: Number> Visitor<'_> for NumberSeed<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(T::EXPECTING)
+    }
+
+    #[inline]
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<T, E> {
+        T::from_i64(v).ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    #[inline]
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<T, E> {
+        T::from_u64(v).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    #[inline]
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<T, E> {
+        T::from_f64(v).ok_or_else(|| E::invalid_value(Unexpected::Float(v), &self))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+        T::from_str(v).ok_or_else(|| {
+            // a number out of range is a wrong value, anything else a wrong type. `parse()`
+            // takes words like `inf` as well, which are not numbers here
+            let is_number = v.parse::<f64>().is_ok()
+                && !v.contains(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E');
+            if is_number {
+                E::invalid_value(Unexpected::Str(v), &self)
+            } else {
+                E::invalid_type(Unexpected::Str(v), &self)
+            }
+        })
+    }
+}
+
+/// Deserializes a `bytes` field from a base64 or hex string without an intermediate `String`, or
+/// from a native byte string in non-human-readable formats. `decode` decodes the string.
+#[derive(Clone, Copy)]
+pub struct BytesSeed {
+    pub decode: fn(&str) -> Option<Vec<u8>>,
+    pub expecting: &'static str,
+}
+
+impl<'de> DeserializeSeed<'de> for BytesSeed {
+    type Value = Vec<u8>;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(self)
+        } else {
+            DeserializeBytes::deserialize(deserializer).map(|v| v.0)
+        }
+    }
+}
+
+impl Visitor<'_> for BytesSeed {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    #[inline]
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+        (self.decode)(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+/// Deserializes a `bytes` field from an array of numbers, or from a native byte string in
+/// non-human-readable formats.
+#[derive(Clone, Copy)]
+pub struct ByteArraySeed;
+
+impl<'de> DeserializeSeed<'de> for ByteArraySeed {
+    type Value = Vec<u8>;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_seq(self)
+        } else {
+            DeserializeBytes::deserialize(deserializer).map(|v| v.0)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for ByteArraySeed {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of bytes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// The protobuf JSON mapping accepts both the standard and the URL-safe alphabet, with or without
+/// padding.
+const BASE64_DECODE_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const BASE64_STANDARD: GeneralPurpose =
+    GeneralPurpose::new(&alphabet::STANDARD, BASE64_DECODE_CONFIG);
+const BASE64_URL_SAFE: GeneralPurpose =
+    GeneralPurpose::new(&alphabet::URL_SAFE, BASE64_DECODE_CONFIG);
+
+/// Reads bytes from base64 in either alphabet, with or without padding, for `BytesSeed`.
+pub fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    if value.contains(['-', '_']) {
+        BASE64_URL_SAFE.decode(value).ok()
+    } else {
+        BASE64_STANDARD.decode(value).ok()
+    }
+}
+
+macro_rules! impl_encode_base64 {
+    ($($name:ident => $engine:ident),*) => {
+        $(
+            /// Writes bytes as base64 by the engine of the same name, for `BytesAs`.
+            pub fn $name(value: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+                Display::fmt(&Base64Display::new(value, &general_purpose::$engine), f)
+            }
+        )*
+    };
+}
+
+impl_encode_base64!(
+    encode_base64 => STANDARD,
+    encode_base64_no_pad => STANDARD_NO_PAD,
+    encode_base64_url => URL_SAFE,
+    encode_base64_url_no_pad => URL_SAFE_NO_PAD
+);
+
+/// Writes bytes as lowercase hex, for `BytesAs`.
+pub fn encode_hex(value: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+    value.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+}
+
+/// Reads bytes from hex in either case, for `BytesSeed`.
+pub fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    let pairs = value.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| {
+            let high = char::from(pair[0]).to_digit(16)?;
+            let low = char::from(pair[1]).to_digit(16)?;
+            Some((high << 4 | low) as u8)
+        })
+        .collect()
+}
+
+/// Seed of a oneof field. The value is read by the seed of a member when the key names one, or as
+/// the whole oneof when the key is the field name. The seed of a member gives `None` for an ignored
+/// unknown enum value.
+pub struct OneofSeed<S>(pub Option<S>);
+
+impl<'de, S, T> DeserializeSeed<'de> for OneofSeed<S>
+where
+    S: DeserializeSeed<'de, Value = Option<T>>,
+    T: Deserialize<'de>,
+{
+    type Value = Option<T>;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        match self.0 {
+            Some(seed) => seed.deserialize(deserializer),
+            None => Option::deserialize(deserializer),
+        }
+    }
+}
+
+/// Options of a message which apply to the members of its oneof fields, given by the message to
+/// the seeds of the members.
+#[derive(Clone, Copy)]
+pub struct MemberOptions {
+    pub lenient_enums: bool,
+    pub ignore_unknown_fields: bool,
+}
+
+/// Writes the member of a oneof in human-readable formats, like a field of the same type of the
+/// message holding the oneof. `encode_base64` is the base64 encoder of the message.
+pub trait SerializeMember {
+    fn serialize_member<S: Serializer>(
+        &self,
+        serializer: S,
+        encode_base64: fn(&[u8], &mut fmt::Formatter) -> fmt::Result,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+/// Serializes the member of a oneof field with the base64 encoder of the message.
+pub struct Member<'a, T>(pub &'a T, pub fn(&[u8], &mut fmt::Formatter) -> fmt::Result);
+
+impl<T: SerializeMember> Serialize for Member<'_, T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_member(serializer, self.1)
+    }
+}
+
+/// Writes the value of a field of which the representation depends on the format, shared by all
+/// of the modifiers of the field.
+pub trait SerializeAs<T: ?Sized>: Copy {
+    fn serialize_as<S: Serializer>(self, value: &T, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Writes an enumeration field by its name, or by its number in non-human-readable formats. The
+/// function maps a number to its name, like `as_str_name` of the prost enum.
+#[derive(Clone, Copy)]
+pub struct EnumAs(pub fn(i32) -> Option<&'static str>);
+
+impl SerializeAs<i32> for EnumAs {
+    #[inline]
+    fn serialize_as<S: Serializer>(self, value: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() && !enums_as_integers() {
+            match (self.0)(*value) {
+                Some(name) => serializer.serialize_str(name),
+                None => Err(serde::ser::Error::custom(format_args!(
+                    "Invalid enum value {}",
+                    value
+                ))),
+            }
+        } else {
+            serializer.serialize_i32(*value)
+        }
+    }
+}
+
+/// Writes a floating point field, with the special values as the strings `NaN`, `Infinity` and
+/// `-Infinity` in human-readable formats.
+#[derive(Clone, Copy)]
+pub struct FloatAs;
+
+macro_rules! impl_float_as {
+    ($($ty:ty => $serialize:ident),*) => {
+        $(
+            impl SerializeAs<$ty> for FloatAs {
+                #[inline]
+                fn serialize_as<S: Serializer>(
+                    self,
+                    value: &$ty,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    if !serializer.is_human_readable() || value.is_finite() {
+                        serializer.$serialize(*value)
+                    } else if value.is_nan() {
+                        serializer.serialize_str("NaN")
+                    } else if value.is_sign_positive() {
+                        serializer.serialize_str("Infinity")
+                    } else {
+                        serializer.serialize_str("-Infinity")
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_float_as!(f32 => serialize_f32, f64 => serialize_f64);
+
+/// Writes a `bytes` field as base64 or hex without an intermediate `String`, or as a native byte
+/// string in non-human-readable formats. The function encodes the bytes.
+#[derive(Clone, Copy)]
+pub struct BytesAs(pub fn(&[u8], &mut fmt::Formatter) -> fmt::Result);
+
+impl<T: AsRef<[u8]> + ?Sized> SerializeAs<T> for BytesAs {
+    #[inline]
+    fn serialize_as<S: Serializer>(self, value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Encode<'a>(&'a [u8], fn(&[u8], &mut fmt::Formatter) -> fmt::Result);
+
+        impl Display for Encode<'_> {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                (self.1)(self.0, f)
+            }
+        }
+
+        if serializer.is_human_readable() {
+            serializer.collect_str(&Encode(value.as_ref(), self.0))
+        } else {
+            serializer.serialize_bytes(value.as_ref())
+        }
+    }
+}
+
+/// Writes a `bytes` field as an array of numbers, or as a native byte string in
+/// non-human-readable formats.
+#[derive(Clone, Copy)]
+pub struct ByteArrayAs;
+
+impl<T: AsRef<[u8]> + ?Sized> SerializeAs<T> for ByteArrayAs {
+    #[inline]
+    fn serialize_as<S: Serializer>(self, value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(value.as_ref())
+        } else {
+            serializer.serialize_bytes(value.as_ref())
+        }
+    }
+}
+
+/// Writes a message or oneof field by `SerializeMessage`.
+#[derive(Clone, Copy)]
+pub struct MessageAs;
+
+impl<'a, T: SerializeMessage<'a> + ?Sized> SerializeAs<T> for MessageAs {
+    #[inline]
+    fn serialize_as<S: Serializer>(self, value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        value.serialize_message(serializer)
+    }
+}
+
+/// Serializes a field without modifiers with `A`.
+pub struct Single<'a, T, A>(pub &'a T, pub A);
+
+impl<T, A: SerializeAs<T>> Serialize for Single<'_, T, A> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.1.serialize_as(self.0, serializer)
+    }
+}
+
+/// Serializes an optional field with `A`.
+pub struct Optional<'a, T, A>(pub &'a Option<T>, pub A);
+
+impl<T, A: SerializeAs<T>> Serialize for Optional<'_, T, A> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Some(value) => serializer.serialize_some(&Single(value, self.1)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Serializes a repeated field with `A`.
+pub struct Repeated<'a, T, A>(pub &'a [T], pub A);
+
+impl<T, A: SerializeAs<T>> Serialize for Repeated<'_, T, A> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|value| Single(value, self.1)))
+    }
+}
+
+/// Seed of an optional field, made of the seed of its value.
+pub struct OptionSeed<S>(pub S);
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for OptionSeed<S> {
+    type Value = Option<S::Value>;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> Visitor<'de> for OptionSeed<S> {
+    type Value = Option<S::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("option")
+    }
+
+    #[inline]
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(deserializer).map(Some)
+    }
+}
+
+/// Seed of a repeated field, made of the seed of its values.
+pub struct RepeatedSeed<S> {
+    pub seed: S,
+    /// Whether a string is read as the comma-separated values, in human-readable formats.
+    pub split_strings: bool,
+}
+
+impl<'de, S: DeserializeSeed<'de> + Copy> DeserializeSeed<'de> for RepeatedSeed<S> {
+    type Value = Vec<S::Value>;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        if self.split_strings && deserializer.is_human_readable() {
+            deserializer.deserialize_any(self)
+        } else {
+            deserializer.deserialize_seq(self)
+        }
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de> + Copy> Visitor<'de> for RepeatedSeed<S> {
+    type Value = Vec<S::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(value) = seq.next_element_seed(self.seed)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if !self.split_strings {
+            return Err(E::invalid_type(Unexpected::Str(v), &self));
+        }
+        if v.is_empty() {
+            return Ok(Vec::new());
+        }
+        v.split(',')
+            .map(|value| self.seed.deserialize(StrDeserializer::<E>::new(value)))
+            .collect()
+    }
+}
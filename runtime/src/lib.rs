@@ -0,0 +1,12 @@
+//! Derive macros for serializing/deserializing structs generated by [Prost] with [Serde].
+//!
+//! [Prost]: https://github.com/tokio-rs/prost
+//! [Serde]: https://serde.rs
+
+pub mod options;
+pub mod report;
+
+#[doc(hidden)]
+pub mod __private;
+
+pub use prost_serde_derive_impl::{Deserialize, Serialize};
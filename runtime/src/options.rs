@@ -0,0 +1,252 @@
+//! Options changing how messages are parsed and printed at runtime, on top of the attributes of
+//! `#[prost_serde_derive(...)]`.
+//!
+//! ```ignore
+//! use prost_serde_derive::options::{ParseOptions, PrintOptions};
+//! use serde::de::DeserializeSeed;
+//!
+//! let message: Message = ParseOptions::new()
+//!     .ignore_unknown_fields(true)
+//!     .seed()
+//!     .deserialize(&mut deserializer)?;
+//!
+//! let json = serde_json::to_string(&PrintOptions::new().emit_defaults(false).wrap(&message))?;
+//! ```
+//!
+//! The options apply to every message deserialized or serialized within the call, including the
+//! nested ones. A parse option which is set overrides the attribute of the same name either way,
+//! so that the messages with lenient attributes can still be parsed strictly.
+
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Options for deserializing messages.
+///
+/// Each option which is set turns the behavior of the attribute of the same name on or off for
+/// every message, whether the message has the attribute or not. The options which are not set
+/// keep the attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) ignore_unknown_fields: Option<bool>,
+    pub(crate) omit_type_errors: Option<bool>,
+    pub(crate) use_default_for_missing_fields: Option<bool>,
+    pub(crate) lenient_enums: Option<bool>,
+    pub(crate) string_input: Option<bool>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ParseOptions {
+    const DEFAULT: Self = Self {
+        ignore_unknown_fields: None,
+        omit_type_errors: None,
+        use_default_for_missing_fields: None,
+        lenient_enums: None,
+        string_input: None,
+    };
+
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips fields which are not in the message instead of failing.
+    pub fn ignore_unknown_fields(mut self, value: bool) -> Self {
+        self.ignore_unknown_fields = Some(value);
+        self
+    }
+
+    /// Replaces values with the default instead of failing on type errors.
+    pub fn omit_type_errors(mut self, value: bool) -> Self {
+        self.omit_type_errors = Some(value);
+        self
+    }
+
+    /// Fills non-optional fields missing from the input with the default value.
+    pub fn use_default_for_missing_fields(mut self, value: bool) -> Self {
+        self.use_default_for_missing_fields = Some(value);
+        self
+    }
+
+    /// Matches enum names case-insensitively and with or without the prefix of the enum, e.g.
+    /// `english`, `English` and `LANGUAGE_ENGLISH` for `LANGUAGE_ENGLISH`.
+    pub fn lenient_enums(mut self, value: bool) -> Self {
+        self.lenient_enums = Some(value);
+        self
+    }
+
+    /// Reads booleans from `"true"` and `"false"`, and repeated fields from comma-separated
+    /// strings or keys given more than once, for inputs where every value is a string like
+    /// environment variables and query strings. Numbers are always read from strings.
+    pub fn string_input(mut self, value: bool) -> Self {
+        self.string_input = Some(value);
+        self
+    }
+
+    /// Returns a seed deserializing `T` with these options.
+    #[inline]
+    pub fn seed<T>(self) -> ParseSeed<T> {
+        ParseSeed {
+            options: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Deserializes `T` with [`ParseOptions`].
+pub struct ParseSeed<T> {
+    options: ParseOptions,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for ParseSeed<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ParseSeed<T> {}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for ParseSeed<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<T, D::Error> {
+        let _scope = Scope::enter(&PARSE_OPTIONS, self.options);
+        T::deserialize(deserializer)
+    }
+}
+
+/// Options for serializing messages. The default serializes messages like their `Serialize`
+/// implementation does.
+///
+/// The options only change human-readable formats, as non-human-readable formats have to write
+/// every field in order to read them back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrintOptions {
+    pub(crate) emit_defaults: bool,
+    pub(crate) json_names: bool,
+    pub(crate) enums_as_integers: bool,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl PrintOptions {
+    const DEFAULT: Self = Self {
+        emit_defaults: true,
+        json_names: false,
+        enums_as_integers: false,
+    };
+
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes fields which have the default value, e.g. zeros, empty strings, empty repeated
+    /// fields and absent messages. Enabled by default.
+    pub fn emit_defaults(mut self, value: bool) -> Self {
+        self.emit_defaults = value;
+        self
+    }
+
+    /// Writes the lowerCamelCase JSON names of the fields instead of the names in the proto.
+    /// Parsing accepts both of them.
+    pub fn json_names(mut self, value: bool) -> Self {
+        self.json_names = value;
+        self
+    }
+
+    /// Writes enums as their numbers instead of their names. Parsing accepts both of them.
+    pub fn enums_as_integers(mut self, value: bool) -> Self {
+        self.enums_as_integers = value;
+        self
+    }
+
+    /// Wraps `value` to serialize it with these options.
+    #[inline]
+    pub fn wrap<T: ?Sized>(self, value: &T) -> Print<'_, T> {
+        Print {
+            options: self,
+            value,
+        }
+    }
+}
+
+/// Serializes `T` with [`PrintOptions`].
+pub struct Print<'a, T: ?Sized> {
+    options: PrintOptions,
+    value: &'a T,
+}
+
+impl<T: ?Sized> Clone for Print<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Print<'_, T> {}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Print<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: ?Sized + Serialize> Serialize for Print<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let _scope = Scope::enter(&PRINT_OPTIONS, self.options);
+        self.value.serialize(serializer)
+    }
+}
+
+thread_local! {
+    static PARSE_OPTIONS: Cell<ParseOptions> = const { Cell::new(ParseOptions::DEFAULT) };
+    static PRINT_OPTIONS: Cell<PrintOptions> = const { Cell::new(PrintOptions::DEFAULT) };
+}
+
+/// Options of the innermost call, restored to the ones of the outer call when dropped.
+struct Scope<T: Copy + 'static> {
+    key: &'static std::thread::LocalKey<Cell<T>>,
+    previous: T,
+}
+
+impl<T: Copy + 'static> Scope<T> {
+    fn enter(key: &'static std::thread::LocalKey<Cell<T>>, options: T) -> Self {
+        Self {
+            key,
+            previous: key.with(|current| current.replace(options)),
+        }
+    }
+}
+
+impl<T: Copy + 'static> Drop for Scope<T> {
+    fn drop(&mut self) {
+        let previous = self.previous;
+        self.key.with(|current| current.set(previous));
+    }
+}
+
+/// Returns the options of the current call, or the default ones outside of any.
+#[inline]
+pub(crate) fn parse_options() -> ParseOptions {
+    PARSE_OPTIONS.with(Cell::get)
+}
+
+#[inline]
+pub(crate) fn print_options() -> PrintOptions {
+    PRINT_OPTIONS.with(Cell::get)
+}
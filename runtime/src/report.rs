@@ -0,0 +1,117 @@
+//! Collecting the errors swallowed by `#[prost_serde_derive(omit_type_errors)]`.
+//!
+//! ```ignore
+//! let (message, omitted) =
+//!     prost_serde_derive::report::deserialize_with_report::<Message, _>(&mut deserializer)?;
+//! for error in omitted {
+//!     log::warn!("{}", error);
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+
+/// A value that has been replaced with the default because of a type error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OmittedError {
+    /// Dot-separated path to the field, e.g. `address.street`.
+    pub path: String,
+    /// Message of the error raised while deserializing the value.
+    pub message: String,
+}
+
+impl fmt::Display for OmittedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Collector {
+    path: Vec<String>,
+    errors: Vec<OmittedError>,
+}
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Collector>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn enter(name: &str) -> bool {
+    COLLECTOR.with(|collector| match collector.borrow_mut().as_mut() {
+        Some(collector) => {
+            collector.path.push(name.to_owned());
+            true
+        }
+        None => false,
+    })
+}
+
+pub(crate) fn leave() {
+    COLLECTOR.with(|collector| {
+        if let Some(collector) = collector.borrow_mut().as_mut() {
+            collector.path.pop();
+        }
+    })
+}
+
+pub(crate) fn omit<E: fmt::Display>(name: &str, error: &E) {
+    COLLECTOR.with(|collector| {
+        if let Some(collector) = collector.borrow_mut().as_mut() {
+            let path = collector
+                .path
+                .iter()
+                .map(String::as_str)
+                .chain([name])
+                .collect::<Vec<_>>()
+                .join(".");
+            collector.errors.push(OmittedError {
+                path,
+                message: error.to_string(),
+            });
+        }
+    })
+}
+
+struct Session {
+    previous: Option<Collector>,
+}
+
+impl Session {
+    fn start() -> Self {
+        Self {
+            previous: COLLECTOR.with(|collector| collector.replace(Some(Collector::default()))),
+        }
+    }
+
+    fn finish(self) -> Collector {
+        COLLECTOR
+            .with(|collector| collector.borrow_mut().take())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        COLLECTOR.with(|collector| *collector.borrow_mut() = previous);
+    }
+}
+
+/// Deserializes `T` and returns every error that has been omitted along the way, including the
+/// ones from nested messages.
+///
+/// Only types with `#[prost_serde_derive(omit_type_errors)]` omit errors; for the other types
+/// the returned list is always empty.
+pub fn deserialize_with_report<'de, T, D>(
+    deserializer: D,
+) -> Result<(T, Vec<OmittedError>), D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let session = Session::start();
+    let value = T::deserialize(deserializer)?;
+    Ok((value, session.finish().errors))
+}
@@ -1,6 +1,11 @@
 fn main() {
     let mut config = prost_build::Config::new();
-    config.bytes(["testproto.User.api_keys"]);
+    config.bytes([
+        "testproto.User.api_keys",
+        ".bytes.Blobs",
+        ".bytes.Encodings",
+        ".bytes.Attachment",
+    ]);
 
     let builder = tonic_build::configure()
         .out_dir("src/proto")
@@ -44,6 +49,14 @@ fn main() {
             "message.PostCode",
             "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
         )
+        .type_attribute(
+            "message.Node",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "message.Branch",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
         .type_attribute(
             "oneof.Oneof",
             "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
@@ -64,6 +77,14 @@ fn main() {
             "oneof.Wolf",
             "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
         )
+        .type_attribute(
+            "oneof.Value",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "oneof.Value.kind",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
         .type_attribute(
             "options.Message",
             "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
@@ -71,8 +92,205 @@ fn main() {
         .type_attribute(
             "options.Message",
             "#[prost_serde_derive(omit_type_errors, use_default_for_missing_fields, ignore_unknown_fields)]",
+        )
+        .type_attribute(
+            "report.Report",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "report.Inner",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute("report.Inner", "#[prost_serde_derive(omit_type_errors)]")
+        .type_attribute(
+            "bytes.Blobs",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "bytes.UrlSafeBlobs",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "bytes.UrlSafeBlobs",
+            r#"#[prost_serde_derive(base64_output = "url_safe_no_pad")]"#,
+        )
+        .type_attribute(
+            "bytes.Attachment",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "bytes.Attachment",
+            r#"#[prost_serde_derive(base64_output = "url_safe_no_pad")]"#,
+        )
+        .type_attribute(
+            "bytes.Attachment.content",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .field_attribute(
+            "bytes.Attachment.content.digest",
+            r#"#[prost_serde_derive(bytes_encoding = "hex")]"#,
+        )
+        .type_attribute(
+            "binary.Blob",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "binary.Blob.content",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "binary.Kind",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "rename.Request",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "rename.Request",
+            r#"#[prost_serde_derive(rename_all = "camelCase")]"#,
+        )
+        .type_attribute(
+            "rename.Request.filter",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "rename.Request.filter",
+            r#"#[prost_serde_derive(rename_all_variants = "SCREAMING_SNAKE_CASE")]"#,
+        )
+        .type_attribute(
+            "rename.Header",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "rename.Header",
+            r#"#[prost_serde_derive(rename_all = "kebab-case")]"#,
+        )
+        .field_attribute(
+            "rename.Header.content_length",
+            r#"#[prost_serde_derive(rename = "size")]"#,
+        )
+        .type_attribute(
+            "style.Paint",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "style.Color",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "style.Color",
+            r#"#[prost_serde_derive(enum_style = "strip_prefix_lower")]"#,
+        )
+        .type_attribute(
+            "style.Size",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "style.Size",
+            r#"#[prost_serde_derive(enum_style = "strip_prefix")]"#,
+        )
+        .type_attribute(
+            "style.Brush",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute("style.Brush", "#[prost_serde_derive(lenient_enums)]")
+        .type_attribute(
+            "style.Brush.tip",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "lenient.Settings",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute("lenient.Settings", "#[prost_serde_derive(lenient_enums)]")
+        .type_attribute(
+            "lenient.Mode",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute("lenient.Mode", "#[prost_serde_derive(lenient_enums)]")
+        .type_attribute(
+            "numbers.Numbers",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "numbers.Measure",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "numbers.Measure.value",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "string_input.Config",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute("string_input.Config", "#[prost_serde_derive(string_input)]")
+        .type_attribute(
+            "string_input.Level",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "fields.Account",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .field_attribute(
+            "fields.Account.user_name",
+            "#[prost_serde_derive(rename = \"login\", alias = \"username\", alias = \"user\")]",
+        )
+        .field_attribute(
+            "fields.Account.score",
+            "#[prost_serde_derive(skip_serializing_if_default)]",
+        )
+        .field_attribute(
+            "fields.Account.level",
+            "#[prost_serde_derive(default = \"crate::defaults::level\", omit_type_errors)]",
+        )
+        .field_attribute(
+            "fields.Account.roles",
+            "#[prost_serde_derive(default = \"crate::defaults::roles\")]",
+        )
+        .type_attribute(
+            "reexport.Reexport",
+            "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+        )
+        .type_attribute(
+            "reexport.Reexport",
+            "#[prost_serde_derive(crate = \"crate::deps::derive_reexport\", serde = \"crate::deps::serde_reexport\", ignore_unknown_fields)]",
         );
 
+    // the same encodings for both storages of bytes
+    let builder =
+        ["bytes.Encodings", "bytes.VecEncodings"]
+            .into_iter()
+            .fold(builder, |builder, message| {
+                builder
+                    .type_attribute(
+                        message,
+                        "#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]",
+                    )
+                    .field_attribute(
+                        format!("{message}.hash"),
+                        r#"#[prost_serde_derive(bytes_encoding = "hex")]"#,
+                    )
+                    .field_attribute(
+                        format!("{message}.token"),
+                        r#"#[prost_serde_derive(bytes_encoding = "base64url")]"#,
+                    )
+                    .field_attribute(
+                        format!("{message}.ids"),
+                        r#"#[prost_serde_derive(bytes_encoding = "hex")]"#,
+                    )
+                    .field_attribute(
+                        format!("{message}.legacy"),
+                        r#"#[prost_serde_derive(bytes_encoding = "array")]"#,
+                    )
+                    .field_attribute(
+                        format!("{message}.legacy_chunks"),
+                        r#"#[prost_serde_derive(bytes_encoding = "array")]"#,
+                    )
+            });
+
     builder
         .compile_with_config(
             config,
@@ -85,6 +303,16 @@ fn main() {
                 "proto/message.proto",
                 "proto/oneof.proto",
                 "proto/options.proto",
+                "proto/report.proto",
+                "proto/reexport.proto",
+                "proto/binary.proto",
+                "proto/bytes.proto",
+                "proto/fields.proto",
+                "proto/rename.proto",
+                "proto/style.proto",
+                "proto/lenient.proto",
+                "proto/numbers.proto",
+                "proto/string_input.proto",
             ],
             &["proto"],
         )
@@ -0,0 +1,9 @@
+//! Default values given to fields with `#[prost_serde_derive(default = "...")]`.
+
+pub fn level() -> i32 {
+    1
+}
+
+pub fn roles() -> Vec<String> {
+    vec!["guest".to_string()]
+}
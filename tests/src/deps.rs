@@ -0,0 +1,4 @@
+//! Dependencies re-exported under other paths, as done by facade crates.
+
+pub use prost_serde_derive as derive_reexport;
+pub use serde as serde_reexport;
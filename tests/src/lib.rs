@@ -1,2 +1,4 @@
+pub mod defaults;
+pub mod deps;
 pub mod proto;
 pub mod util;
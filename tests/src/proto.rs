@@ -1,8 +1,19 @@
+pub mod binary;
+pub mod bytes;
 pub mod empty;
 pub mod enums;
+pub mod fields;
+pub mod lenient;
 pub mod message;
+pub mod numbers;
+#[allow(clippy::module_inception)]
 pub mod oneof;
 pub mod optional;
 pub mod options;
 pub mod primitive;
+pub mod reexport;
+pub mod rename;
 pub mod repeated;
+pub mod report;
+pub mod string_input;
+pub mod style;
@@ -0,0 +1,109 @@
+use std::fmt::Debug;
+
+use pretty_assertions::assert_eq;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tests::proto::{binary, primitive};
+
+const PAYLOAD: [u8; 6] = [0x00, 0xff, 0xe2, 0x3f, 0x7f, 0x80];
+
+fn proto() -> binary::Blob {
+    binary::Blob {
+        content: Some(binary::blob::Content::Data(PAYLOAD.to_vec())),
+        checksum: vec![0xde, 0xad, 0xbe, 0xef],
+        kind: binary::Kind::Video as i32,
+        size: 1 << 40,
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|v| v == needle)
+}
+
+fn cbor<T: Serialize>(message: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    ciborium::into_writer(message, &mut buf).unwrap();
+    buf
+}
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(message: T) {
+    let encoded = cbor(&message);
+    assert_eq!(
+        ciborium::from_reader::<T, _>(&encoded[..]).unwrap(),
+        message
+    );
+
+    let encoded = rmp_serde::to_vec(&message).unwrap();
+    assert_eq!(rmp_serde::from_slice::<T>(&encoded).unwrap(), message);
+
+    let encoded = rmp_serde::to_vec_named(&message).unwrap();
+    assert_eq!(rmp_serde::from_slice::<T>(&encoded).unwrap(), message);
+}
+
+#[test]
+fn round_trip_blob() {
+    round_trip(proto());
+    round_trip(binary::Blob {
+        content: Some(binary::blob::Content::Text("text".to_string())),
+        ..proto()
+    });
+    round_trip(binary::Blob {
+        content: None,
+        ..proto()
+    });
+}
+
+#[test]
+fn round_trip_primitive() {
+    round_trip(primitive::Primitive {
+        id: 39,
+        timestamp: 100000000000000,
+        name: "name".to_string(),
+        hashed_password: vec![0xff, 0xe2, 0x3f],
+        is_active: true,
+    });
+}
+
+#[test]
+fn native_bytes() {
+    for encoded in [
+        cbor(&proto()),
+        rmp_serde::to_vec(&proto()).unwrap(),
+        rmp_serde::to_vec_named(&proto()).unwrap(),
+    ] {
+        assert!(contains(&encoded, &PAYLOAD));
+        assert!(contains(&encoded, &[0xde, 0xad, 0xbe, 0xef]));
+    }
+}
+
+#[test]
+fn native_enum() {
+    // `{"kind": 2}` instead of `{"kind": "KIND_VIDEO"}`
+    let encoded = rmp_serde::to_vec_named(&proto()).unwrap();
+    assert!(contains(&encoded, &[0xa4, b'k', b'i', b'n', b'd', 0x02]));
+    assert!(!contains(&encoded, b"KIND_VIDEO"));
+}
+
+#[test]
+fn byte_string_keys() {
+    use ciborium::Value;
+
+    let key = |name: &str| Value::Bytes(name.as_bytes().to_vec());
+    let encoded = cbor(&Value::Map(vec![
+        (key("text"), Value::Text("text".to_string())),
+        (key("checksum"), Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef])),
+        (key("kind"), Value::Integer(2.into())),
+        (key("size"), Value::Integer((1i64 << 40).into())),
+    ]));
+    assert_eq!(
+        ciborium::from_reader::<binary::Blob, _>(&encoded[..]).unwrap(),
+        binary::Blob {
+            content: Some(binary::blob::Content::Text("text".to_string())),
+            ..proto()
+        }
+    );
+
+    let encoded = cbor(&Value::Map(vec![(key("sizes"), Value::Integer(0.into()))]));
+    let error = ciborium::from_reader::<binary::Blob, _>(&encoded[..]).unwrap_err();
+    assert!(error.to_string().contains("unknown field `sizes`"));
+}
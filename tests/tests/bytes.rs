@@ -0,0 +1,165 @@
+use pretty_assertions::assert_eq;
+use prost::bytes::Bytes;
+use tests::proto::bytes;
+use tests::serde_test;
+
+const JSON: &str = r#"{"data":"/+I/","extra":"AA==","chunks":["AQID","","c2VyZGU="]}"#;
+
+fn proto() -> bytes::Blobs {
+    bytes::Blobs {
+        data: Bytes::from_static(&[0xff, 0xe2, 0x3f]),
+        extra: Some(Bytes::from_static(&[0x00])),
+        chunks: vec![
+            Bytes::from_static(&[0x01, 0x02, 0x03]),
+            Bytes::new(),
+            Bytes::from_static(b"serde"),
+        ],
+    }
+}
+
+serde_test!(bytes::Blobs, JSON, proto());
+
+#[test]
+fn invalid_base64() {
+    const JSON: &str = r#"{"data":"/+I/","extra":null,"chunks":["AQID","not base64"]}"#;
+    let err = serde_json::from_str::<bytes::Blobs>(JSON).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with(r#"invalid value: string "not base64", expected a base64 string"#));
+}
+
+#[test]
+fn base64_variants() {
+    // URL-safe and unpadded base64 are read as well
+    for json in [
+        r#"{"data":"_-I_","extra":"AA","chunks":["AQID","","c2VyZGU"]}"#,
+        r#"{"data":"/+I/","extra":"AA","chunks":["AQID","","c2VyZGU="]}"#,
+        r#"{"data":"_-I_","extra":"AA==","chunks":["AQID","","c2VyZGU="]}"#,
+    ] {
+        assert_eq!(serde_json::from_str::<bytes::Blobs>(json).unwrap(), proto());
+    }
+
+    // but not both alphabets at once
+    const JSON: &str = r#"{"data":"/-I_","extra":null,"chunks":[]}"#;
+    assert!(serde_json::from_str::<bytes::Blobs>(JSON).is_err());
+}
+
+#[test]
+fn base64_output() {
+    const JSON: &str = r#"{"data":"_-I_","extra":"AA","chunks":["AQID","","c2VyZGU"]}"#;
+
+    let message = bytes::UrlSafeBlobs {
+        data: vec![0xff, 0xe2, 0x3f],
+        extra: Some(vec![0x00]),
+        chunks: vec![vec![0x01, 0x02, 0x03], vec![], b"serde".to_vec()],
+    };
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(
+        serde_json::from_str::<bytes::UrlSafeBlobs>(JSON).unwrap(),
+        message
+    );
+    assert_eq!(
+        serde_json::from_str::<bytes::UrlSafeBlobs>(
+            r#"{"data":"/+I/","extra":"AA==","chunks":["AQID","","c2VyZGU="]}"#
+        )
+        .unwrap(),
+        message
+    );
+}
+
+#[test]
+fn bytes_encoding() {
+    const JSON: &str = r#"{"hash":"00ff7f","token":"_-I_","ids":["0102","","abcdef"],"legacy":[255,0],"legacy_chunks":[[1,2],[]]}"#;
+
+    let message = bytes::Encodings {
+        hash: Bytes::from_static(&[0x00, 0xff, 0x7f]),
+        token: Some(Bytes::from_static(&[0xff, 0xe2, 0x3f])),
+        ids: vec![
+            Bytes::from_static(&[0x01, 0x02]),
+            Bytes::new(),
+            Bytes::from_static(&[0xab, 0xcd, 0xef]),
+        ],
+        legacy: Some(Bytes::from_static(&[0xff, 0x00])),
+        legacy_chunks: vec![Bytes::from_static(&[0x01, 0x02]), Bytes::new()],
+    };
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(
+        serde_json::from_str::<bytes::Encodings>(JSON).unwrap(),
+        message
+    );
+
+    let message = bytes::VecEncodings {
+        hash: vec![0x00, 0xff, 0x7f],
+        token: Some(vec![0xff, 0xe2, 0x3f]),
+        ids: vec![vec![0x01, 0x02], vec![], vec![0xab, 0xcd, 0xef]],
+        legacy: Some(vec![0xff, 0x00]),
+        legacy_chunks: vec![vec![0x01, 0x02], vec![]],
+    };
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(
+        serde_json::from_str::<bytes::VecEncodings>(JSON).unwrap(),
+        message
+    );
+
+    // hex is read in either case, and base64url like any base64
+    let message = serde_json::from_str::<bytes::VecEncodings>(
+        r#"{"hash":"00FF7f","token":"/+I/","ids":[],"legacy":null,"legacy_chunks":[]}"#,
+    )
+    .unwrap();
+    assert_eq!(message.hash, vec![0x00, 0xff, 0x7f]);
+    assert_eq!(message.token, Some(vec![0xff, 0xe2, 0x3f]));
+
+    for (json, expected) in [
+        (
+            r#"{"hash":"0ff","token":null,"ids":[],"legacy":null,"legacy_chunks":[]}"#,
+            r#"invalid value: string "0ff", expected a hex string"#,
+        ),
+        (
+            r#"{"hash":"","token":null,"ids":[],"legacy":"AA==","legacy_chunks":[]}"#,
+            r#"invalid type: string "AA==", expected an array of bytes"#,
+        ),
+        (
+            r#"{"hash":"","token":null,"ids":[],"legacy":[256],"legacy_chunks":[]}"#,
+            "invalid value: integer `256`, expected u8",
+        ),
+    ] {
+        let err = serde_json::from_str::<bytes::Encodings>(json).unwrap_err();
+        assert!(err.to_string().starts_with(expected), "{err}");
+    }
+}
+
+#[test]
+fn oneof_members() {
+    use bytes::attachment::Content;
+
+    // the members are written with the base64 output of the message and their own encodings
+    for (content, json) in [
+        (
+            Content::Raw(Bytes::from_static(&[0xff, 0xe2, 0x3f])),
+            r#"{"raw":"_-I_"}"#,
+        ),
+        (
+            Content::Digest(Bytes::from_static(&[0x00, 0xff, 0x7f])),
+            r#"{"digest":"00ff7f"}"#,
+        ),
+    ] {
+        let message = bytes::Attachment {
+            content: Some(content),
+        };
+        assert_eq!(serde_json::to_string(&message).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<bytes::Attachment>(json).unwrap(),
+            message
+        );
+    }
+
+    let message = serde_json::from_str::<bytes::Attachment>(r#"{"raw":"/+I/"}"#).unwrap();
+    assert_eq!(
+        message.content,
+        Some(Content::Raw(Bytes::from_static(&[0xff, 0xe2, 0x3f])))
+    );
+    let err = serde_json::from_str::<bytes::Attachment>(r#"{"digest":"AA=="}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with(r#"invalid value: string "AA==", expected a hex string"#));
+}
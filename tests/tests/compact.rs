@@ -0,0 +1,130 @@
+use std::fmt::Debug;
+
+use pretty_assertions::assert_eq;
+use prost::bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tests::proto::{bytes, enums, message, oneof, optional, primitive, repeated};
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(message: T) {
+    let encoded = bincode::serialize(&message).unwrap();
+    assert_eq!(bincode::deserialize::<T>(&encoded).unwrap(), message);
+
+    let encoded = postcard::to_allocvec(&message).unwrap();
+    assert_eq!(postcard::from_bytes::<T>(&encoded).unwrap(), message);
+}
+
+#[test]
+fn primitive() {
+    round_trip(primitive::Primitive {
+        id: 39,
+        timestamp: 100000000000000,
+        name: "name".to_string(),
+        hashed_password: vec![0xff, 0xe2, 0x3f],
+        is_active: true,
+    });
+}
+
+#[test]
+fn optional() {
+    round_trip(optional::Optional {
+        id: Some(39),
+        timestamp: None,
+        name: Some("name".to_string()),
+        hashed_password: Some(vec![0xff, 0xe2, 0x3f]),
+        is_active: None,
+    });
+}
+
+#[test]
+fn repeated() {
+    round_trip(repeated::Repeated {
+        id: 39,
+        timestamp: 100000000000000,
+        names: vec!["name".to_string(), "is".to_string()],
+        hashed_password: vec![vec![0xff], vec![0xe2, 0x3f]],
+        is_active: Some(true),
+    });
+}
+
+#[test]
+fn bytes_encoding() {
+    // every encoding is a native byte string here
+    round_trip(bytes::Encodings {
+        hash: Bytes::from_static(&[0x00, 0xff]),
+        token: Some(Bytes::from_static(&[0xe2])),
+        ids: vec![Bytes::from_static(&[0x01]), Bytes::new()],
+        legacy: None,
+        legacy_chunks: vec![Bytes::from_static(&[0x3f])],
+    });
+}
+
+#[test]
+fn enums() {
+    round_trip(enums::Enum {
+        language: enums::Language::English as i32,
+        notification: Some(enums::Notification::Email as i32),
+        sub_notification: None,
+        spoken_languages: vec![enums::Language::Spanish as i32],
+    });
+}
+
+#[test]
+fn message() {
+    round_trip(message::Message {
+        address: Some(message::Address {
+            street: None,
+            city: Some("city".to_string()),
+            state: "state".to_string(),
+        }),
+        post_code: Some(message::PostCode {
+            code: "code".to_string(),
+        }),
+        history: vec![message::Address::default()],
+    });
+}
+
+#[test]
+fn oneof() {
+    round_trip(oneof::Oneof {
+        animal: Some(oneof::oneof::Animal::Dog(oneof::Dog {
+            name: "name".to_string(),
+            breed: "breed".to_string(),
+        })),
+        is_wild: Some(false),
+        age: Some(3),
+    });
+    round_trip(oneof::Oneof {
+        animal: None,
+        is_wild: None,
+        age: None,
+    });
+}
+
+#[test]
+fn native_encoding() {
+    let message = primitive::Primitive {
+        id: 1,
+        timestamp: 2,
+        name: String::new(),
+        hashed_password: vec![0xff, 0xe2, 0x3f],
+        is_active: true,
+    };
+    // bytes are written as-is instead of as a base64 string
+    assert_eq!(
+        postcard::to_allocvec(&message).unwrap(),
+        vec![0x02, 0x04, 0x00, 0x03, 0xff, 0xe2, 0x3f, 0x01]
+    );
+
+    let message = enums::Enum {
+        language: enums::Language::French as i32,
+        notification: None,
+        sub_notification: None,
+        spoken_languages: vec![],
+    };
+    // enums are written as their values instead of their names
+    assert_eq!(
+        postcard::to_allocvec(&message).unwrap(),
+        vec![0x04, 0x00, 0x00, 0x00]
+    );
+}
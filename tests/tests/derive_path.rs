@@ -0,0 +1,107 @@
+use pretty_assertions::assert_eq;
+use prost::{Enumeration, Message};
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, Message)]
+pub struct Imported {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(enumeration = "Color", tag = "2")]
+    pub color: i32,
+}
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enumeration)]
+pub enum Color {
+    Red = 0,
+    Blue = 1,
+}
+
+impl Color {
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Color::Red => "RED",
+            Color::Blue => "BLUE",
+        }
+    }
+
+    pub fn from_str_name(value: &str) -> Option<Self> {
+        match value {
+            "RED" => Some(Self::Red),
+            "BLUE" => Some(Self::Blue),
+            _ => None,
+        }
+    }
+}
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Qualified {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+}
+
+mod other {
+    pub use core::hash::Hash as Message;
+}
+
+// a derive of another crate with the same name does not count
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, other::Message, prost::Message)]
+pub struct Unrelated {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+}
+
+#[derive(
+    Clone, PartialEq, prost::Message, prost_serde_derive::Deserialize, prost_serde_derive::Serialize,
+)]
+#[prost_serde_derive(message)]
+pub struct Explicit {
+    #[prost(int32, tag = "1")]
+    pub id: i32,
+}
+
+#[test]
+fn imported() {
+    const JSON: &str = r#"{"name":"name","color":"BLUE"}"#;
+    let message = Imported {
+        name: "name".to_string(),
+        color: Color::Blue as i32,
+    };
+
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(serde_json::from_str::<Imported>(JSON).unwrap(), message);
+    assert_eq!(serde_json::to_string(&Color::Red).unwrap(), r#""RED""#);
+}
+
+#[test]
+fn qualified() {
+    const JSON: &str = r#"{"id":1}"#;
+    let message = Qualified { id: 1 };
+
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(serde_json::from_str::<Qualified>(JSON).unwrap(), message);
+}
+
+#[test]
+fn unrelated() {
+    const JSON: &str = r#"{"id":1}"#;
+    let message = Unrelated { id: 1 };
+
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(serde_json::from_str::<Unrelated>(JSON).unwrap(), message);
+}
+
+#[test]
+fn explicit() {
+    const JSON: &str = r#"{"id":1}"#;
+    let message = Explicit { id: 1 };
+
+    assert_eq!(serde_json::to_string(&message).unwrap(), JSON);
+    assert_eq!(serde_json::from_str::<Explicit>(JSON).unwrap(), message);
+}
@@ -2,14 +2,46 @@ use pretty_assertions::assert_eq;
 use tests::proto::enums;
 use tests::serde_test;
 
-const JSON: &str = r#"{"language":"LANGUAGE_ENGLISH","notification":"NOTIFICATION_EMAIL","sub_notification":null}"#;
+const JSON: &str = r#"{"language":"LANGUAGE_ENGLISH","notification":"NOTIFICATION_EMAIL","sub_notification":null,"spoken_languages":["LANGUAGE_FRENCH","LANGUAGE_SPANISH"]}"#;
 
 fn proto() -> enums::Enum {
     enums::Enum {
         language: enums::Language::English as i32,
         notification: Some(enums::Notification::Email as i32),
         sub_notification: None,
+        spoken_languages: vec![
+            enums::Language::French as i32,
+            enums::Language::Spanish as i32,
+        ],
     }
 }
 
 serde_test!(enums::Enum, JSON, proto());
+
+#[test]
+fn standalone() {
+    assert_eq!(
+        serde_json::from_str::<enums::Language>(r#""LANGUAGE_SPANISH""#).unwrap(),
+        enums::Language::Spanish
+    );
+    assert_eq!(
+        serde_json::to_string(&enums::Language::Spanish).unwrap(),
+        r#""LANGUAGE_SPANISH""#
+    );
+}
+
+#[test]
+fn unknown_variant() {
+    const JSON: &str = r#"{"language":"LANGUAGE_KLINGON","spoken_languages":[]}"#;
+    let err = serde_json::from_str::<enums::Enum>(JSON).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("unknown variant `LANGUAGE_KLINGON`"));
+
+    const REPEATED_JSON: &str =
+        r#"{"language":"LANGUAGE_ENGLISH","spoken_languages":["LANGUAGE_KLINGON"]}"#;
+    let err = serde_json::from_str::<enums::Enum>(REPEATED_JSON).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("unknown variant `LANGUAGE_KLINGON`"));
+}
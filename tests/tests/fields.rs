@@ -0,0 +1,95 @@
+use pretty_assertions::assert_eq;
+use tests::proto::fields;
+use tests::serde_test;
+
+const JSON: &str =
+    r#"{"login":"login","score":10,"level":3,"roles":["admin"],"display_name":"name"}"#;
+
+fn proto() -> fields::Account {
+    fields::Account {
+        user_name: "login".to_string(),
+        score: 10,
+        level: 3,
+        roles: vec!["admin".to_string()],
+        display_name: "name".to_string(),
+    }
+}
+
+serde_test!(fields::Account, JSON, proto());
+
+#[test]
+fn alias() {
+    for json in [
+        r#"{"username":"login","score":10,"level":3,"roles":["admin"],"displayName":"name"}"#,
+        r#"{"user":"login","score":10,"level":3,"roles":["admin"],"display_name":"name"}"#,
+    ] {
+        assert_eq!(
+            serde_json::from_str::<fields::Account>(json).unwrap(),
+            proto()
+        );
+    }
+
+    // the name in the proto is replaced by the new name
+    let err = serde_json::from_str::<fields::Account>(r#"{"user_name":"login"}"#).unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `user_name`"));
+}
+
+#[test]
+fn skip_serializing_if_default() {
+    let message = fields::Account {
+        score: 0,
+        ..proto()
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    assert_eq!(
+        json,
+        r#"{"login":"login","level":3,"roles":["admin"],"display_name":"name"}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<fields::Account>(&json).unwrap(),
+        message
+    );
+}
+
+#[test]
+fn default() {
+    let message =
+        serde_json::from_str::<fields::Account>(r#"{"login":"login","score":1,"display_name":""}"#)
+            .unwrap();
+    assert_eq!(
+        message,
+        fields::Account {
+            user_name: "login".to_string(),
+            score: 1,
+            level: 1,
+            roles: vec!["guest".to_string()],
+            display_name: String::new(),
+        }
+    );
+
+    // other fields are still required
+    let err =
+        serde_json::from_str::<fields::Account>(r#"{"login":"login","score":1}"#).unwrap_err();
+    assert!(err.to_string().starts_with("missing field `display_name`"));
+}
+
+#[test]
+fn omit_type_errors() {
+    let message = serde_json::from_str::<fields::Account>(
+        r#"{"login":"login","score":10,"level":"high","roles":["admin"],"display_name":"name"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        message,
+        fields::Account {
+            level: 1,
+            ..proto()
+        }
+    );
+
+    let err = serde_json::from_str::<fields::Account>(
+        r#"{"login":"login","score":"high","level":3,"roles":["admin"],"display_name":"name"}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with("invalid type"));
+}
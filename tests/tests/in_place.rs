@@ -0,0 +1,111 @@
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+use tests::proto::{message, oneof, options, repeated};
+
+fn deserialize_in_place<'de, T: Deserialize<'de>>(json: &'de str, place: &mut T) {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    T::deserialize_in_place(&mut deserializer, place).unwrap();
+}
+
+#[test]
+fn in_place() {
+    const JSON: &str = r#"{"id":39,"timestamp":1,"names":["name","is"],"hashed_password":["/+I/"],"is_active":null}"#;
+
+    let mut place = repeated::Repeated {
+        id: 1,
+        timestamp: 2,
+        names: Vec::with_capacity(16),
+        hashed_password: vec![],
+        is_active: Some(true),
+    };
+    let names = place.names.as_ptr();
+    deserialize_in_place(JSON, &mut place);
+
+    assert_eq!(
+        place,
+        serde_json::from_str::<repeated::Repeated>(JSON).unwrap()
+    );
+    assert_eq!(place.names.as_ptr(), names);
+}
+
+#[test]
+fn in_place_nested() {
+    const JSON: &str = r#"{"address":{"street":"street","city":null,"state":"state"},"post_code":null,"history":[{"state":"state"}]}"#;
+
+    let mut place = message::Message {
+        address: Some(message::Address {
+            street: Some(String::with_capacity(64)),
+            city: Some("city".to_string()),
+            state: String::with_capacity(64),
+        }),
+        post_code: Some(message::PostCode {
+            code: "code".to_string(),
+        }),
+        history: vec![message::Address {
+            state: String::with_capacity(64),
+            ..Default::default()
+        }],
+    };
+    let address = place.address.as_ref().unwrap();
+    let street = address.street.as_ref().unwrap().as_ptr();
+    let state = address.state.as_ptr();
+    let history_state = place.history[0].state.as_ptr();
+    deserialize_in_place(JSON, &mut place);
+
+    assert_eq!(
+        place,
+        serde_json::from_str::<message::Message>(JSON).unwrap()
+    );
+    let address = place.address.as_ref().unwrap();
+    assert_eq!(address.street.as_ref().unwrap().as_ptr(), street);
+    assert_eq!(address.state.as_ptr(), state);
+    assert_eq!(place.history[0].state.as_ptr(), history_state);
+}
+
+#[test]
+fn in_place_oneof() {
+    const JSON: &str = r#"{"is_wild":true,"age":null,"wolf":{"name":"name"}}"#;
+
+    let mut place = oneof::Oneof {
+        animal: Some(oneof::oneof::Animal::Cat(oneof::Cat::default())),
+        is_wild: None,
+        age: Some(3),
+    };
+    deserialize_in_place(JSON, &mut place);
+
+    assert_eq!(place, serde_json::from_str::<oneof::Oneof>(JSON).unwrap());
+}
+
+#[test]
+fn in_place_reset_missing_fields() {
+    let mut place = options::Message {
+        address: "address".to_string(),
+        post_code: Some(1),
+        is_valid: true,
+    };
+    deserialize_in_place("{}", &mut place);
+    assert_eq!(place, options::Message::default());
+
+    let mut place = repeated::Repeated {
+        names: vec!["name".to_string()],
+        is_active: Some(true),
+        ..Default::default()
+    };
+    deserialize_in_place(r#"{"id":1,"timestamp":2,"hashed_password":[]}"#, &mut place);
+    assert_eq!(
+        place,
+        repeated::Repeated {
+            id: 1,
+            timestamp: 2,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn in_place_missing_field() {
+    let mut place = repeated::Repeated::default();
+    let mut deserializer = serde_json::Deserializer::from_str(r#"{"id":1}"#);
+    let err = repeated::Repeated::deserialize_in_place(&mut deserializer, &mut place).unwrap_err();
+    assert!(err.to_string().starts_with("missing field `timestamp`"));
+}
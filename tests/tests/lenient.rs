@@ -0,0 +1,61 @@
+use pretty_assertions::assert_eq;
+use tests::proto::lenient;
+
+#[test]
+fn field() {
+    let message = serde_json::from_str::<lenient::Settings>(
+        r#"{"mode":"safe","fallback":"Fast","history":["MODE_SAFE","Safe","unspecified"]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        message,
+        lenient::Settings {
+            mode: lenient::Mode::Safe as i32,
+            fallback: Some(lenient::Mode::Fast as i32),
+            history: vec![
+                lenient::Mode::Safe as i32,
+                lenient::Mode::Safe as i32,
+                lenient::Mode::Unspecified as i32,
+            ],
+        }
+    );
+
+    // messages are still written with the names in the proto
+    assert_eq!(
+        serde_json::to_string(&message).unwrap(),
+        r#"{"mode":"MODE_SAFE","fallback":"MODE_FAST","history":["MODE_SAFE","MODE_SAFE","MODE_UNSPECIFIED"]}"#
+    );
+}
+
+#[test]
+fn standalone() {
+    for name in [r#""fast""#, r#""FAST""#, r#""MODE_FAST""#] {
+        assert_eq!(
+            serde_json::from_str::<lenient::Mode>(name).unwrap(),
+            lenient::Mode::Fast
+        );
+    }
+    assert_eq!(
+        serde_json::from_str::<lenient::Mode>(r#""MODE_MODE_FAST""#).unwrap(),
+        lenient::Mode::ModeFast
+    );
+    assert!(serde_json::from_str::<lenient::Mode>(r#""slow""#)
+        .unwrap_err()
+        .to_string()
+        .starts_with("unknown variant `slow`"));
+}
+
+#[test]
+fn ambiguous() {
+    // `MODE_FAST` as it is, or `MODE_MODE_FAST` with the prefix
+    let err = serde_json::from_str::<lenient::Mode>(r#""mode_fast""#).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("ambiguous enum value, matches both `MODE_FAST` and `MODE_MODE_FAST`"));
+
+    let err = serde_json::from_str::<lenient::Settings>(
+        r#"{"mode":"mode_fast","fallback":null,"history":[]}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with("ambiguous enum value"));
+}
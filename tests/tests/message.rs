@@ -2,7 +2,7 @@ use pretty_assertions::assert_eq;
 use tests::proto::message;
 use tests::serde_test;
 
-const JSON: &str = r#"{"address":{"street":null,"city":"city","state":"state"},"post_code":null}"#;
+const JSON: &str = r#"{"address":{"street":null,"city":"city","state":"state"},"post_code":null,"history":[{"street":"street","city":null,"state":"state"}]}"#;
 
 fn proto() -> message::Message {
     message::Message {
@@ -12,7 +12,35 @@ fn proto() -> message::Message {
             state: "state".to_string(),
         }),
         post_code: None,
+        history: vec![message::Address {
+            street: Some("street".to_string()),
+            city: None,
+            state: "state".to_string(),
+        }],
     }
 }
 
 serde_test!(message::Message, JSON, proto());
+
+#[test]
+fn recursive_message() {
+    let json = r#"{"name":"root","parent":{"name":"parent","parent":null,"branches":[]},"branches":[{"nodes":[{"name":"leaf","parent":null,"branches":[]}]}]}"#;
+    let node = message::Node {
+        name: "root".to_string(),
+        parent: Some(Box::new(message::Node {
+            name: "parent".to_string(),
+            parent: None,
+            branches: vec![],
+        })),
+        branches: vec![message::Branch {
+            nodes: vec![message::Node {
+                name: "leaf".to_string(),
+                parent: None,
+                branches: vec![],
+            }],
+        }],
+    };
+
+    assert_eq!(serde_json::to_string(&node).unwrap(), json);
+    assert_eq!(serde_json::from_str::<message::Node>(json).unwrap(), node);
+}
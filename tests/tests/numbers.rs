@@ -0,0 +1,152 @@
+use pretty_assertions::assert_eq;
+use tests::proto::numbers;
+use tests::serde_test;
+
+const JSON: &str = r#"{"int32":-1,"uint32":2,"int64":-3,"uint64":4,"fixed32":5,"sfixed64":-6,"float":7.5,"double":8.25,"optional_double":null,"floats":[1.0],"int64s":[9],"sint32":-10,"sint64":11}"#;
+
+fn proto() -> numbers::Numbers {
+    numbers::Numbers {
+        int32: -1,
+        uint32: 2,
+        int64: -3,
+        uint64: 4,
+        fixed32: 5,
+        sfixed64: -6,
+        float: 7.5,
+        double: 8.25,
+        optional_double: None,
+        floats: vec![1.0],
+        int64s: vec![9],
+        sint32: -10,
+        sint64: 11,
+    }
+}
+
+serde_test!(numbers::Numbers, JSON, proto());
+
+fn parse(fields: &str) -> serde_json::Result<numbers::Numbers> {
+    let mut json = serde_json::to_value(proto()).unwrap();
+    let fields: serde_json::Map<_, _> = serde_json::from_str(fields).unwrap();
+    json.as_object_mut().unwrap().extend(fields);
+    serde_json::from_value(json)
+}
+
+#[test]
+fn integer_representations() {
+    let message = parse(
+        r#"{"int32":"-42","uint32":1e3,"int64":"9223372036854775807","uint64":5.0,"fixed32":"1e2","int64s":["1",2.0,3],"sint32":"-7","sint64":"-9223372036854775808"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        message,
+        numbers::Numbers {
+            int32: -42,
+            uint32: 1000,
+            int64: i64::MAX,
+            uint64: 5,
+            fixed32: 100,
+            int64s: vec![1, 2, 3],
+            sint32: -7,
+            sint64: i64::MIN,
+            ..proto()
+        }
+    );
+}
+
+#[test]
+fn integer_errors() {
+    for (fields, message) in [
+        (
+            r#"{"int32":2147483648}"#,
+            "invalid value: integer `2147483648`",
+        ),
+        (r#"{"uint32":-1}"#, "invalid value: integer `-1`"),
+        (
+            r#"{"sint32":-2147483649}"#,
+            "invalid value: integer `-2147483649`",
+        ),
+        (
+            r#"{"int64":"9223372036854775808"}"#,
+            "invalid value: string \"9223372036854775808\"",
+        ),
+        (r#"{"uint64":1.5}"#, "invalid value: floating point `1.5`"),
+        (r#"{"fixed32":"1.5"}"#, "invalid value: string \"1.5\""),
+        (r#"{"int32":"one"}"#, "invalid type: string \"one\""),
+        (r#"{"int32":true}"#, "invalid type: boolean `true`"),
+    ] {
+        let err = parse(fields).unwrap_err().to_string();
+        assert!(err.starts_with(message), "{}: {}", fields, err);
+    }
+}
+
+#[test]
+fn float_representations() {
+    let message = parse(
+        r#"{"float":"NaN","double":"-Infinity","optional_double":"1.5","floats":["Infinity",2,"3e1"]}"#,
+    )
+    .unwrap();
+    assert!(message.float.is_nan());
+    assert_eq!(message.double, f64::NEG_INFINITY);
+    assert_eq!(message.optional_double, Some(1.5));
+    assert_eq!(message.floats, vec![f32::INFINITY, 2.0, 30.0]);
+
+    for (fields, message) in [
+        (r#"{"float":1e39}"#, "invalid value: floating point"),
+        (r#"{"double":"inf"}"#, "invalid type: string \"inf\""),
+        (r#"{"double":"1e400"}"#, "invalid value: string \"1e400\""),
+    ] {
+        let err = parse(fields).unwrap_err().to_string();
+        assert!(err.starts_with(message), "{}: {}", fields, err);
+    }
+}
+
+#[test]
+fn special_floats() {
+    let message = numbers::Numbers {
+        float: f32::NAN,
+        double: f64::INFINITY,
+        optional_double: Some(f64::NEG_INFINITY),
+        floats: vec![f32::NEG_INFINITY, 1.5],
+        ..proto()
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    assert_eq!(
+        json,
+        r#"{"int32":-1,"uint32":2,"int64":-3,"uint64":4,"fixed32":5,"sfixed64":-6,"float":"NaN","double":"Infinity","optional_double":"-Infinity","floats":["-Infinity",1.5],"int64s":[9],"sint32":-10,"sint64":11}"#
+    );
+
+    let parsed = serde_json::from_str::<numbers::Numbers>(&json).unwrap();
+    assert!(parsed.float.is_nan());
+    // NaN is not equal to itself
+    assert_eq!(
+        numbers::Numbers {
+            float: 0.0,
+            ..parsed
+        },
+        numbers::Numbers {
+            float: 0.0,
+            ..message
+        }
+    );
+}
+
+#[test]
+fn oneof_members() {
+    use numbers::measure::Value;
+
+    let message = serde_json::from_str::<numbers::Measure>(r#"{"count":"5"}"#).unwrap();
+    assert_eq!(message.value, Some(Value::Count(5)));
+    let message = serde_json::from_str::<numbers::Measure>(r#"{"ratio":"-Infinity"}"#).unwrap();
+    assert_eq!(message.value, Some(Value::Ratio(f64::NEG_INFINITY)));
+
+    let message = numbers::Measure {
+        value: Some(Value::Ratio(f64::NAN)),
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    assert_eq!(json, r#"{"ratio":"NaN"}"#);
+    let parsed = serde_json::from_str::<numbers::Measure>(&json).unwrap();
+    assert!(matches!(parsed.value, Some(Value::Ratio(v)) if v.is_nan()));
+
+    let err = serde_json::from_str::<numbers::Measure>(r#"{"count":"1.5"}"#).unwrap_err();
+    assert!(err.to_string().starts_with("invalid value: string \"1.5\""));
+}
@@ -16,3 +16,59 @@ fn proto() -> oneof::Oneof {
 }
 
 serde_test!(oneof::Oneof, JSON, proto());
+
+#[test]
+fn scalar_members() {
+    for (json, kind) in [
+        (
+            r#"{"display_name":"name"}"#,
+            oneof::value::Kind::DisplayName("name".to_string()),
+        ),
+        (r#"{"number":42}"#, oneof::value::Kind::Number(42)),
+        (r#"{"flag":false}"#, oneof::value::Kind::Flag(false)),
+    ] {
+        let message = oneof::Value { kind: Some(kind) };
+        assert_eq!(serde_json::to_string(&message).unwrap(), json);
+        assert_eq!(serde_json::from_str::<oneof::Value>(json).unwrap(), message);
+    }
+}
+
+#[test]
+fn json_names() {
+    // members are also accepted by their lowerCamelCase JSON names
+    assert_eq!(
+        serde_json::from_str::<oneof::Value>(r#"{"displayName":"name"}"#).unwrap(),
+        oneof::Value {
+            kind: Some(oneof::value::Kind::DisplayName("name".to_string())),
+        }
+    );
+    assert_eq!(
+        serde_json::from_str::<oneof::Oneof>(
+            r#"{"isWild":false,"dog":{"name":"name","breed":"breed"}}"#
+        )
+        .unwrap(),
+        oneof::Oneof {
+            animal: Some(oneof::oneof::Animal::Dog(oneof::Dog {
+                name: "name".to_string(),
+                breed: "breed".to_string(),
+            })),
+            is_wild: Some(false),
+            age: None,
+        }
+    );
+}
+
+#[test]
+fn unknown_member() {
+    let err = serde_json::from_str::<oneof::Value>(r#"{"text":"name"}"#).unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `text`"), "{err}");
+
+    let err = serde_json::from_str::<oneof::Oneof>(r#"{"fox":{"name":"name"}}"#).unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `fox`"), "{err}");
+}
+
+#[test]
+fn duplicate_member() {
+    let err = serde_json::from_str::<oneof::Value>(r#"{"number":1,"flag":true}"#).unwrap_err();
+    assert!(err.to_string().starts_with("duplicate field"), "{err}");
+}
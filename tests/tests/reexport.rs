@@ -0,0 +1,23 @@
+use pretty_assertions::assert_eq;
+use tests::proto::reexport;
+use tests::serde_test;
+
+const JSON: &str = r#"{"name":"name","data":"/+I/"}"#;
+
+fn proto() -> reexport::Reexport {
+    reexport::Reexport {
+        name: "name".to_string(),
+        data: vec![0xff, 0xe2, 0x3f],
+    }
+}
+
+serde_test!(reexport::Reexport, JSON, proto());
+
+#[test]
+fn ignore_unknown_fields() {
+    const UNKNOWN_JSON: &str = r#"{"name":"name","data":"/+I/","unknown":{"a":[1,2]}}"#;
+    assert_eq!(
+        serde_json::from_str::<reexport::Reexport>(UNKNOWN_JSON).unwrap(),
+        proto()
+    );
+}
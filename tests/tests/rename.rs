@@ -0,0 +1,48 @@
+use pretty_assertions::assert_eq;
+use tests::proto::rename;
+use tests::serde_test;
+
+const JSON: &str = r#"{"userId":"user","pageSize":10,"OWNER_ID":3}"#;
+
+fn proto() -> rename::Request {
+    rename::Request {
+        user_id: "user".to_string(),
+        page_size: 10,
+        filter: Some(rename::request::Filter::OwnerId(3)),
+    }
+}
+
+serde_test!(rename::Request, JSON, proto());
+
+#[test]
+fn original_names() {
+    // renamed fields are only keyed by their new names
+    let err =
+        serde_json::from_str::<rename::Request>(r#"{"user_id":"user","pageSize":10}"#).unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `user_id`"));
+
+    let err =
+        serde_json::from_str::<rename::Request>(r#"{"userId":"user","pageSize":10,"owner_id":3}"#)
+            .unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `owner_id`"));
+}
+
+#[test]
+fn field_rename() {
+    let message = rename::Header {
+        content_type: "text/plain".to_string(),
+        content_length: 5,
+        content_md5: "XUFAKrxLKna5cZ2REBfFkg==".to_string(),
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    // the rename of the field takes precedence over `rename_all`
+    // digits stay attached to the preceding word
+    assert_eq!(
+        json,
+        r#"{"content-type":"text/plain","size":5,"content-md5":"XUFAKrxLKna5cZ2REBfFkg=="}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<rename::Header>(&json).unwrap(),
+        message
+    );
+}
@@ -0,0 +1,58 @@
+use pretty_assertions::assert_eq;
+use prost_serde_derive::report::deserialize_with_report;
+use tests::proto::{options, report};
+
+#[test]
+fn report() {
+    const JSON: &str = r#"{"address":"address","post_code":"zip","is_valid":true}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let (message, omitted) =
+        deserialize_with_report::<options::Message, _>(&mut deserializer).unwrap();
+
+    assert_eq!(
+        message,
+        options::Message {
+            address: "address".to_string(),
+            post_code: Some(0),
+            is_valid: true,
+        }
+    );
+    assert_eq!(
+        omitted.iter().map(|v| &v.path[..]).collect::<Vec<_>>(),
+        vec!["post_code"]
+    );
+}
+
+#[test]
+fn report_nested() {
+    const JSON: &str = r#"{"inner":{"count":"many","is_valid":"yes"},"id":1}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let (message, omitted) =
+        deserialize_with_report::<report::Report, _>(&mut deserializer).unwrap();
+
+    assert_eq!(
+        message,
+        report::Report {
+            inner: Some(report::Inner {
+                count: 0,
+                is_valid: false,
+            }),
+            id: 1,
+        }
+    );
+    assert_eq!(
+        omitted.iter().map(|v| &v.path[..]).collect::<Vec<_>>(),
+        vec!["inner.count", "inner.is_valid"]
+    );
+}
+
+#[test]
+fn report_outside_session() {
+    const JSON: &str = r#"{"inner":{"count":"many","is_valid":true},"id":1}"#;
+    let message = serde_json::from_str::<report::Report>(JSON).unwrap();
+    assert_eq!(message.inner.unwrap().count, 0);
+
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let (_, omitted) = deserialize_with_report::<report::Report, _>(&mut deserializer).unwrap();
+    assert_eq!(omitted.len(), 1);
+}
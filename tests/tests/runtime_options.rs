@@ -0,0 +1,255 @@
+use pretty_assertions::assert_eq;
+use prost_serde_derive::options::{ParseOptions, PrintOptions};
+use serde::de::DeserializeSeed;
+use serde::Deserialize;
+use tests::proto::{enums, message, oneof, primitive};
+
+fn parse<'de, T: Deserialize<'de>>(options: ParseOptions, json: &'de str) -> serde_json::Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    options.seed::<T>().deserialize(&mut deserializer)
+}
+
+#[test]
+fn ignore_unknown_fields() {
+    const JSON: &str = r#"{"address":{"street":null,"city":null,"state":"state","country":"KR"},"history":[],"extra":{}}"#;
+
+    assert!(serde_json::from_str::<message::Message>(JSON).is_err());
+    assert!(parse::<message::Message>(ParseOptions::new(), JSON).is_err());
+
+    let message: message::Message =
+        parse(ParseOptions::new().ignore_unknown_fields(true), JSON).unwrap();
+    assert_eq!(
+        message,
+        message::Message {
+            address: Some(message::Address {
+                street: None,
+                city: None,
+                state: "state".to_string(),
+            }),
+            post_code: None,
+            history: vec![],
+        }
+    );
+
+    // the options only last for the call
+    assert!(serde_json::from_str::<message::Message>(JSON).is_err());
+}
+
+#[test]
+fn ignore_unknown_enum_values() {
+    const JSON: &str = r#"{"language":"LANGUAGE_KOREAN","notification":"NOTIFICATION_FAX","sub_notification":"NOTIFICATION_SMS","spoken_languages":["LANGUAGE_KOREAN","LANGUAGE_FRENCH"]}"#;
+
+    let err = serde_json::from_str::<enums::Enum>(JSON).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("unknown variant `LANGUAGE_KOREAN`"));
+
+    // unknown values are left at the default, and dropped from repeated fields
+    let message: enums::Enum =
+        parse(ParseOptions::new().ignore_unknown_fields(true), JSON).unwrap();
+    assert_eq!(
+        message,
+        enums::Enum {
+            language: enums::Language::Unknown as i32,
+            notification: None,
+            sub_notification: Some(enums::Notification::Sms as i32),
+            spoken_languages: vec![enums::Language::French as i32],
+        }
+    );
+
+    let message: enums::Enum = parse(
+        ParseOptions::new().ignore_unknown_fields(true),
+        r#"{"language":42,"spoken_languages":[42,2]}"#,
+    )
+    .unwrap();
+    assert_eq!(message.language, enums::Language::Unknown as i32);
+    assert_eq!(
+        message.spoken_languages,
+        vec![enums::Language::French as i32]
+    );
+}
+
+#[test]
+fn omit_type_errors() {
+    const JSON: &str =
+        r#"{"id":"one","timestamp":2,"name":"name","hashed_password":"","is_active":true}"#;
+
+    assert!(serde_json::from_str::<primitive::Primitive>(JSON).is_err());
+
+    let message: primitive::Primitive =
+        parse(ParseOptions::new().omit_type_errors(true), JSON).unwrap();
+    assert_eq!(
+        message,
+        primitive::Primitive {
+            id: 0,
+            timestamp: 2,
+            name: "name".to_string(),
+            hashed_password: vec![],
+            is_active: true,
+        }
+    );
+}
+
+#[test]
+fn strict_parsing() {
+    use tests::proto::{lenient, options as attributed};
+
+    // the options turn off the attributes of the messages as well
+    let strict = ParseOptions::new()
+        .omit_type_errors(false)
+        .use_default_for_missing_fields(false)
+        .ignore_unknown_fields(false);
+    for (json, message) in [
+        (
+            r#"{"address":"","post_code":null,"is_valid":"FFFF"}"#,
+            "invalid type",
+        ),
+        (
+            r#"{"address":"","post_code":null}"#,
+            "missing field `is_valid`",
+        ),
+        (
+            r#"{"address":"","post_code":null,"is_valid":true,"phone":""}"#,
+            "unknown field `phone`",
+        ),
+    ] {
+        assert!(serde_json::from_str::<attributed::Message>(json).is_ok());
+        let err = parse::<attributed::Message>(strict, json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with(message), "{}: {}", json, err);
+    }
+
+    const JSON: &str = r#"{"mode":"fast","fallback":null,"history":[]}"#;
+    assert!(serde_json::from_str::<lenient::Settings>(JSON).is_ok());
+    assert!(parse::<lenient::Settings>(ParseOptions::new().lenient_enums(false), JSON).is_err());
+}
+
+#[test]
+fn use_default_for_missing_fields() {
+    const JSON: &str = r#"{"timestamp":2}"#;
+
+    assert!(serde_json::from_str::<primitive::Primitive>(JSON).is_err());
+
+    let options = ParseOptions::new().use_default_for_missing_fields(true);
+    let message: primitive::Primitive = parse(options, JSON).unwrap();
+    assert_eq!(
+        message,
+        primitive::Primitive {
+            timestamp: 2,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn lenient_enums() {
+    const JSON: &str = r#"{"language":"french","notification":"Sms","sub_notification":null,"spoken_languages":["LANGUAGE_ENGLISH","english"]}"#;
+
+    assert!(serde_json::from_str::<enums::Enum>(JSON).is_err());
+
+    let message: enums::Enum = parse(ParseOptions::new().lenient_enums(true), JSON).unwrap();
+    assert_eq!(
+        message,
+        enums::Enum {
+            language: enums::Language::French as i32,
+            notification: Some(enums::Notification::Sms as i32),
+            sub_notification: None,
+            spoken_languages: vec![
+                enums::Language::English as i32,
+                enums::Language::English as i32,
+            ],
+        }
+    );
+    assert_eq!(
+        parse::<enums::Language>(ParseOptions::new().lenient_enums(true), r#""spanish""#).unwrap(),
+        enums::Language::Spanish
+    );
+}
+
+#[test]
+fn json_names() {
+    let message = primitive::Primitive {
+        id: 1,
+        timestamp: 2,
+        name: "name".to_string(),
+        hashed_password: vec![0xff],
+        is_active: true,
+    };
+    let json = serde_json::to_string(&PrintOptions::new().json_names(true).wrap(&message)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"id":1,"timestamp":2,"name":"name","hashedPassword":"/w==","isActive":true}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<primitive::Primitive>(&json).unwrap(),
+        message
+    );
+
+    let message = oneof::Oneof {
+        animal: Some(oneof::oneof::Animal::Wolf(oneof::Wolf {
+            name: "name".to_string(),
+        })),
+        is_wild: Some(true),
+        age: None,
+    };
+    let json = serde_json::to_string(&PrintOptions::new().json_names(true).wrap(&message)).unwrap();
+    assert_eq!(json, r#"{"isWild":true,"age":null,"wolf":{"name":"name"}}"#);
+    assert_eq!(
+        serde_json::from_str::<oneof::Oneof>(&json).unwrap(),
+        message
+    );
+}
+
+#[test]
+fn emit_defaults() {
+    let message = message::Message {
+        address: Some(message::Address {
+            street: None,
+            city: Some(String::new()),
+            state: String::new(),
+        }),
+        post_code: None,
+        history: vec![],
+    };
+    let json =
+        serde_json::to_string(&PrintOptions::new().emit_defaults(false).wrap(&message)).unwrap();
+    assert_eq!(json, r#"{"address":{"city":""}}"#);
+
+    let parsed: message::Message = parse(
+        ParseOptions::new().use_default_for_missing_fields(true),
+        &json,
+    )
+    .unwrap();
+    assert_eq!(parsed, message);
+}
+
+#[test]
+fn enums_as_integers() {
+    let message = enums::Enum {
+        language: enums::Language::French as i32,
+        notification: Some(enums::Notification::Sms as i32),
+        sub_notification: None,
+        spoken_languages: vec![enums::Language::English as i32],
+    };
+    let options = PrintOptions::new().enums_as_integers(true);
+    let json = serde_json::to_string(&options.wrap(&message)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"language":2,"notification":2,"sub_notification":null,"spoken_languages":[1]}"#
+    );
+    assert_eq!(serde_json::from_str::<enums::Enum>(&json).unwrap(), message);
+
+    let json = serde_json::to_string(&options.wrap(&enums::Language::Spanish)).unwrap();
+    assert_eq!(json, "3");
+    assert_eq!(
+        serde_json::from_str::<enums::Language>(&json).unwrap(),
+        enums::Language::Spanish
+    );
+    assert!(serde_json::from_str::<enums::Language>("42").is_err());
+    assert!(serde_json::from_str::<enums::Enum>(r#"{"language":42}"#).is_err());
+    assert_eq!(
+        serde_json::to_string(&message).unwrap(),
+        r#"{"language":"LANGUAGE_FRENCH","notification":"NOTIFICATION_SMS","sub_notification":null,"spoken_languages":["LANGUAGE_ENGLISH"]}"#
+    );
+}
@@ -0,0 +1,115 @@
+use pretty_assertions::assert_eq;
+use prost_serde_derive::options::ParseOptions;
+use serde::de::value::{Error, MapDeserializer};
+use serde::de::DeserializeSeed;
+use serde::Deserialize;
+use tests::proto::{repeated, string_input};
+
+fn config() -> string_input::Config {
+    string_input::Config {
+        debug: true,
+        port: 8080,
+        ratio: 0.5,
+        hosts: vec!["a.example".to_string(), "b.example".to_string()],
+        levels: vec![
+            string_input::Level::Info as i32,
+            string_input::Level::Debug as i32,
+        ],
+        ids: vec![1, 2],
+    }
+}
+
+#[test]
+fn strings() {
+    let message = serde_json::from_str::<string_input::Config>(
+        r#"{"debug":"true","port":"8080","ratio":"0.5","hosts":"a.example,b.example","levels":"LEVEL_INFO,LEVEL_DEBUG","ids":"1,2"}"#,
+    )
+    .unwrap();
+    assert_eq!(message, config());
+
+    // values of their own types are still read
+    let message = serde_json::from_str::<string_input::Config>(
+        r#"{"debug":true,"port":8080,"ratio":0.5,"hosts":["a.example","b.example"],"levels":["LEVEL_INFO","LEVEL_DEBUG"],"ids":["1",2]}"#,
+    )
+    .unwrap();
+    assert_eq!(message, config());
+
+    let message = serde_json::from_str::<string_input::Config>(
+        r#"{"debug":"false","port":"0","ratio":"0","hosts":"","levels":"","ids":""}"#,
+    )
+    .unwrap();
+    assert_eq!(message, string_input::Config::default());
+
+    assert!(
+        serde_json::from_str::<string_input::Config>(r#"{"debug":"yes"}"#)
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid value: string \"yes\", expected a boolean")
+    );
+    assert!(
+        serde_json::from_str::<string_input::Config>(r#"{"levels":"LEVEL_INFO,LEVEL_TRACE"}"#)
+            .unwrap_err()
+            .to_string()
+            .starts_with("unknown variant `LEVEL_TRACE`")
+    );
+}
+
+#[test]
+fn repeated_keys() {
+    let pairs = [
+        ("debug", "true"),
+        ("port", "8080"),
+        ("ratio", "0.5"),
+        ("hosts", "a.example"),
+        ("levels", "LEVEL_INFO"),
+        ("hosts", "b.example"),
+        ("ids", "1,2"),
+        ("levels", "LEVEL_DEBUG"),
+    ];
+
+    let deserializer = MapDeserializer::<_, Error>::new(pairs.into_iter());
+    let message = string_input::Config::deserialize(deserializer).unwrap();
+    assert_eq!(message, config());
+
+    let mut message = string_input::Config {
+        hosts: vec!["c.example".to_string()],
+        ..Default::default()
+    };
+    let deserializer = MapDeserializer::<_, Error>::new(pairs.into_iter());
+    string_input::Config::deserialize_in_place(deserializer, &mut message).unwrap();
+    assert_eq!(message, config());
+
+    // other fields are still given once
+    let deserializer = MapDeserializer::<_, Error>::new([("port", "1"), ("port", "2")].into_iter());
+    assert_eq!(
+        string_input::Config::deserialize(deserializer)
+            .unwrap_err()
+            .to_string(),
+        "duplicate field `port`"
+    );
+}
+
+#[test]
+fn runtime_option() {
+    const JSON: &str =
+        r#"{"id":"1","timestamp":"2","names":"a,b","hashed_password":[],"is_active":"true"}"#;
+
+    assert!(serde_json::from_str::<repeated::Repeated>(JSON).is_err());
+
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let message = ParseOptions::new()
+        .string_input(true)
+        .seed::<repeated::Repeated>()
+        .deserialize(&mut deserializer)
+        .unwrap();
+    assert_eq!(
+        message,
+        repeated::Repeated {
+            id: 1,
+            timestamp: 2,
+            names: vec!["a".to_string(), "b".to_string()],
+            hashed_password: vec![],
+            is_active: Some(true),
+        }
+    );
+}
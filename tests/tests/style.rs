@@ -0,0 +1,106 @@
+use pretty_assertions::assert_eq;
+use prost_serde_derive::options::{ParseOptions, PrintOptions};
+use serde::de::DeserializeSeed;
+use tests::proto::style;
+use tests::serde_test;
+
+const JSON: &str =
+    r#"{"color":"dark_red","accent":"blue2","palette":["unspecified","dark_red"],"size":"LARGE"}"#;
+
+fn proto() -> style::Paint {
+    style::Paint {
+        color: style::Color::DarkRed as i32,
+        accent: Some(style::Color::Blue2 as i32),
+        palette: vec![
+            style::Color::Unspecified as i32,
+            style::Color::DarkRed as i32,
+        ],
+        size: style::Size::Large as i32,
+    }
+}
+
+serde_test!(style::Paint, JSON, proto());
+
+#[test]
+fn standalone() {
+    assert_eq!(
+        serde_json::to_string(&style::Color::DarkRed).unwrap(),
+        r#""dark_red""#
+    );
+    assert_eq!(
+        serde_json::from_str::<style::Color>(r#""blue2""#).unwrap(),
+        style::Color::Blue2
+    );
+    assert_eq!(
+        serde_json::to_string(&style::Size::Small).unwrap(),
+        r#""SMALL""#
+    );
+}
+
+#[test]
+fn proto_names() {
+    // the names in the proto are not accepted anymore
+    assert!(serde_json::from_str::<style::Color>(r#""COLOR_DARK_RED""#).is_err());
+    let err = serde_json::from_str::<style::Paint>(
+        r#"{"color":"COLOR_DARK_RED","accent":null,"palette":[],"size":"LARGE"}"#,
+    )
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("unknown variant `COLOR_DARK_RED`"));
+}
+
+#[test]
+fn enums_as_integers() {
+    let options = PrintOptions::new().enums_as_integers(true);
+    let json = serde_json::to_string(&options.wrap(&proto())).unwrap();
+    assert_eq!(json, r#"{"color":1,"accent":2,"palette":[0,1],"size":1}"#);
+    assert_eq!(
+        serde_json::from_str::<style::Paint>(&json).unwrap(),
+        proto()
+    );
+}
+
+#[test]
+fn oneof_members() {
+    let message = style::Brush {
+        tip: Some(style::brush::Tip::Color(style::Color::DarkRed as i32)),
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    assert_eq!(json, r#"{"color":"dark_red"}"#);
+    assert_eq!(
+        serde_json::from_str::<style::Brush>(&json).unwrap(),
+        message
+    );
+    assert_eq!(
+        serde_json::to_string(&style::Brush {
+            tip: Some(style::brush::Tip::Size(style::Size::Large as i32)),
+        })
+        .unwrap(),
+        r#"{"size":"LARGE"}"#
+    );
+
+    // the options of the message apply to its oneof members
+    assert_eq!(
+        serde_json::from_str::<style::Brush>(r#"{"color":"COLOR_DARK_RED"}"#).unwrap(),
+        message
+    );
+    let json =
+        serde_json::to_string(&PrintOptions::new().enums_as_integers(true).wrap(&message)).unwrap();
+    assert_eq!(json, r#"{"color":1}"#);
+    assert_eq!(
+        serde_json::from_str::<style::Brush>(&json).unwrap(),
+        message
+    );
+
+    // an ignored unknown value leaves the oneof unset
+    const UNKNOWN: &str = r#"{"color":"purple"}"#;
+    assert!(serde_json::from_str::<style::Brush>(UNKNOWN).is_err());
+    let mut deserializer = serde_json::Deserializer::from_str(UNKNOWN);
+    let message = ParseOptions::new()
+        .ignore_unknown_fields(true)
+        .seed::<style::Brush>()
+        .deserialize(&mut deserializer)
+        .unwrap();
+    assert_eq!(message, style::Brush { tip: None });
+}
@@ -0,0 +1,40 @@
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[prost_serde_derive(message, omit_type_errors, unknown_option, enum_style = "strip_prefix")]
+#[prost_serde_derive(base64_output = "hex")]
+pub struct Message {
+    #[prost(string, tag = "1")]
+    #[prost_serde_derive(bytes_encoding = "hex")]
+    pub name: String,
+    #[prost(tag = "2")]
+    pub id: i32,
+    #[prost(enumeration = "not a path", tag = "3")]
+    pub language: i32,
+    #[prost(int32, repeated)]
+    pub values: Vec<i32>,
+    #[prost(int32, optional, tag = "5")]
+    #[prost_serde_derive(default = "default_count", unknown_field_option)]
+    pub count: Option<i32>,
+    #[prost(bytes = "vec", tag = "6")]
+    #[prost_serde_derive(bytes_encoding = "base32")]
+    pub hash: Vec<u8>,
+    #[prost(string, tag = "7")]
+    #[prost_serde_derive(rename = "", alias = "")]
+    pub label: String,
+}
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[prost_serde_derive(enumeration, rename_all = "camelCase", rename_all_variants = "lowercase")]
+#[prost_serde_derive(enum_style = "lower", string_input, base64_output = "url_safe")]
+pub enum Enum {
+    Value = 0,
+}
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[prost_serde_derive(oneof)]
+pub enum Oneof {
+    #[prost(string, tag = "1")]
+    #[prost_serde_derive(rename = "name", skip_serializing_if_default)]
+    Name(String),
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+mod other {
+    pub use core::cmp::Eq as Enumeration;
+    pub use core::hash::Hash as Message;
+}
+
+use other::{Enumeration, Message};
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[derive(PartialEq, other::Message)]
+pub struct Unrelated {
+    pub id: i32,
+}
+
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[derive(PartialEq, Message, Enumeration)]
+pub struct Ambiguous {
+    pub id: i32,
+}
+
+fn main() {}
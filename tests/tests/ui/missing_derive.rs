@@ -0,0 +1,15 @@
+#[derive(prost_serde_derive::Deserialize, prost_serde_derive::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Message {
+    #[prost(message, optional, tag = "1")]
+    pub address: ::core::option::Option<Address>,
+}
+
+#[derive(prost_serde_derive::Serialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Address {
+    #[prost(string, tag = "1")]
+    pub street: ::prost::alloc::string::String,
+}
+
+fn main() {}